@@ -0,0 +1,84 @@
+//! Compares `CheckedAcc` with the operators of `Checked` on the same loops.
+//!
+//! Run with `cargo bench --bench acc`.
+
+extern crate checked;
+
+use checked::{Checked, CheckedAcc};
+use std::hint::black_box;
+use std::time::Instant;
+
+const LEN: usize = 1 << 16;
+const ROUNDS: u32 = 200;
+
+// Runs `f` on the data repeatedly, and returns the nanoseconds per element.
+fn time<F>(data: &[u32], f: F) -> f64
+where
+    F: Fn(&[u32]) -> Checked<u32>,
+{
+    black_box(f(black_box(data)));
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        black_box(f(black_box(data)));
+    }
+    start.elapsed().as_secs_f64() * 1e9 / f64::from(ROUNDS) / data.len() as f64
+}
+
+fn report<F, G>(name: &str, data: &[u32], checked: F, acc: G)
+where
+    F: Fn(&[u32]) -> Checked<u32>,
+    G: Fn(&[u32]) -> Checked<u32>,
+{
+    assert_eq!(checked(data), acc(data));
+    let a = time(data, checked);
+    let b = time(data, acc);
+    println!(
+        "{:<8} Checked {:>6.3} ns/element   CheckedAcc {:>6.3} ns/element   {:.1}x",
+        name,
+        a,
+        b,
+        a / b
+    );
+}
+
+fn sum_checked(data: &[u32]) -> Checked<u32> {
+    let mut sum = Checked::new(0);
+    for &x in data {
+        sum += x;
+    }
+    sum
+}
+
+fn sum_acc(data: &[u32]) -> Checked<u32> {
+    let mut sum = CheckedAcc::new(0);
+    for &x in data {
+        sum += x;
+    }
+    sum.to_checked()
+}
+
+fn dot_checked(data: &[u32]) -> Checked<u32> {
+    let mut sum = Checked::new(0);
+    for w in data.chunks_exact(2) {
+        sum += Checked::new(w[0]) * w[1];
+    }
+    sum
+}
+
+fn dot_acc(data: &[u32]) -> Checked<u32> {
+    let mut sum = CheckedAcc::new(0);
+    for w in data.chunks_exact(2) {
+        sum += CheckedAcc::new(w[0]) * w[1];
+    }
+    sum.to_checked()
+}
+
+fn main() {
+    let small: Vec<u32> = (0..LEN as u32).map(|i| i % 61).collect();
+    report("sum", &small, sum_checked, sum_acc);
+    report("dot", &small, dot_checked, dot_acc);
+
+    // Overflows near the start, after which `Checked` has nothing left to compute.
+    let large: Vec<u32> = (0..LEN as u32).map(|i| u32::MAX - i).collect();
+    report("sum/fail", &large, sum_checked, sum_acc);
+}
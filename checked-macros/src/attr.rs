@@ -0,0 +1,182 @@
+use proc_macro2::{Span, TokenStream};
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::visit_mut::{self, VisitMut};
+use syn::{BinOp, Expr, GenericArgument, Ident, Item, ItemFn, Lit, Type, UnOp};
+
+// What to do when an operation fails.
+enum OnFailure {
+    Panic,
+    Option,
+    Result(Expr),
+}
+
+impl Parse for OnFailure {
+    fn parse(input: ParseStream) -> syn::Result<OnFailure> {
+        if input.is_empty() {
+            return Ok(OnFailure::Panic);
+        }
+        let mode: Ident = input.parse()?;
+        let on_failure = match &*mode.to_string() {
+            "panic" => OnFailure::Panic,
+            "option" => OnFailure::Option,
+            "result" => {
+                input.parse::<Token![=]>()?;
+                OnFailure::Result(input.parse()?)
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    mode.span(),
+                    "expected `panic`, `option` or `result = <error>`",
+                ))
+            }
+        };
+        if !input.is_empty() {
+            return Err(input.error("unexpected tokens after the failure mode"));
+        }
+        Ok(on_failure)
+    }
+}
+
+pub fn checked(args: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    let on_failure = syn::parse2(args)?;
+    let mut function: ItemFn = syn::parse2(item)?;
+    Rewriter { on_failure }.visit_block_mut(&mut function.block);
+    Ok(quote!(#function))
+}
+
+struct Rewriter {
+    on_failure: OnFailure,
+}
+
+impl Rewriter {
+    // The code for a failed `expr`, located at `span`.
+    fn fail(&self, expr: &str, span: Span) -> TokenStream {
+        match self.on_failure {
+            OnFailure::Panic => {
+                let message = format!("arithmetic overflow in `{}`", expr);
+                quote_spanned!(span=> panic!("{}", #message))
+            }
+            OnFailure::Option => quote_spanned!(span=> return None),
+            OnFailure::Result(ref error) => quote_spanned!(span=> return Err(From::from(#error))),
+        }
+    }
+}
+
+// The function a binary operator turns into, and whether it's an assignment.
+pub fn binary_method(op: &BinOp) -> Option<(&'static str, bool)> {
+    Some(match *op {
+        BinOp::Add(_) => ("add", false),
+        BinOp::Sub(_) => ("sub", false),
+        BinOp::Mul(_) => ("mul", false),
+        BinOp::Div(_) => ("div", false),
+        BinOp::Rem(_) => ("rem", false),
+        BinOp::Shl(_) => ("shl", false),
+        BinOp::Shr(_) => ("shr", false),
+        BinOp::AddAssign(_) => ("add", true),
+        BinOp::SubAssign(_) => ("sub", true),
+        BinOp::MulAssign(_) => ("mul", true),
+        BinOp::DivAssign(_) => ("div", true),
+        BinOp::RemAssign(_) => ("rem", true),
+        BinOp::ShlAssign(_) => ("shl", true),
+        BinOp::ShrAssign(_) => ("shr", true),
+        _ => return None,
+    })
+}
+
+// Whether `expr` is a literal that can't be an integer, as in `s + "x"`, where the operator is
+// left to the type's own implementation.
+fn is_other_literal(expr: &Expr) -> bool {
+    match *unparen(expr) {
+        Expr::Lit(ref lit) => !matches!(lit.lit, Lit::Int(_) | Lit::Float(_)),
+        _ => false,
+    }
+}
+
+// Parentheses around an operand are redundant once it's a function argument.
+pub fn unparen(expr: &Expr) -> &Expr {
+    match *expr {
+        Expr::Paren(ref paren) => unparen(&paren.expr),
+        _ => expr,
+    }
+}
+
+impl VisitMut for Rewriter {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        match *expr {
+            // An early return in any of these would leave the wrong thing,
+            // and macro arguments can't be looked into.
+            Expr::Closure(_) | Expr::Async(_) | Expr::Const(_) | Expr::Macro(_) => return,
+            // The length has to stay a constant.
+            Expr::Repeat(ref mut repeat) => return self.visit_expr_mut(&mut repeat.expr),
+            _ => {}
+        }
+        let text = quote!(#expr).to_string();
+        visit_mut::visit_expr_mut(self, expr);
+
+        let replacement = match *expr {
+            Expr::Binary(ref binary) => {
+                let (method, assign) = match binary_method(&binary.op) {
+                    Some(method) => method,
+                    None => return,
+                };
+                if is_other_literal(&binary.left) || is_other_literal(&binary.right) {
+                    return;
+                }
+                // The generated code is reported at the operator (which is where a panic points),
+                // but belongs to the macro, so lints about how it's written don't fire.
+                let span = Span::call_site().located_at(binary.op.span());
+                let method = Ident::new(method, span);
+                let fail = self.fail(&text, span);
+                let (left, right) = (unparen(&binary.left), unparen(&binary.right));
+                if assign {
+                    // Like the primitive operators, this evaluates the right-hand side first.
+                    quote_spanned! {span=>
+                        {
+                            let __checked_rhs = #right;
+                            let __checked_place = &mut #left;
+                            *__checked_place = match ::checked::__private::arith::#method(*__checked_place, __checked_rhs) {
+                                Some(__checked_value) => __checked_value,
+                                None => #fail,
+                            };
+                        }
+                    }
+                } else {
+                    quote_spanned! {span=>
+                        match ::checked::__private::arith::#method(#left, #right) {
+                            Some(__checked_value) => __checked_value,
+                            None => #fail,
+                        }
+                    }
+                }
+            }
+            // Negative literals are fine as they are (and `-128_i8` has to stay a literal).
+            Expr::Unary(ref unary) => match (&unary.op, &*unary.expr) {
+                (UnOp::Neg(_), Expr::Lit(_)) => return,
+                (UnOp::Neg(op), operand) => {
+                    let span = Span::call_site().located_at(op.span());
+                    let fail = self.fail(&text, span);
+                    let operand = unparen(operand);
+                    quote_spanned! {span=>
+                        match ::checked::__private::arith::neg(#operand) {
+                            Some(__checked_value) => __checked_value,
+                            None => #fail,
+                        }
+                    }
+                }
+                _ => return,
+            },
+            _ => return,
+        };
+        *expr = Expr::Verbatim(replacement);
+    }
+
+    // Nested functions and constants are their own business.
+    fn visit_item_mut(&mut self, _: &mut Item) {}
+
+    // So are the constants in types and generic arguments, as in `[u8; N * 2]` or
+    // `f::<{ N + 1 }>()`, which have to stay constant expressions.
+    fn visit_type_mut(&mut self, _: &mut Type) {}
+
+    fn visit_generic_argument_mut(&mut self, _: &mut GenericArgument) {}
+}
@@ -0,0 +1,119 @@
+use proc_macro2::TokenStream;
+use syn::parse::{Parse, ParseStream};
+use syn::{BinOp, Block, Expr, LocalInit, Stmt, UnOp};
+
+// The contents of a `checked!` invocation: statements, or just an expression.
+pub struct Body(Vec<Stmt>);
+
+impl Parse for Body {
+    fn parse(input: ParseStream) -> syn::Result<Body> {
+        Block::parse_within(input).map(Body)
+    }
+}
+
+pub fn checked_block(body: Body) -> TokenStream {
+    let statements = statements(body.0);
+    quote!(::checked::__private::lift({ #(#statements)* }))
+}
+
+// The variables a block binds and assigns hold `Checked` values, and so does its value.
+fn statements(statements: Vec<Stmt>) -> Vec<Stmt> {
+    statements.into_iter().map(statement).collect()
+}
+
+fn statement(statement: Stmt) -> Stmt {
+    match statement {
+        Stmt::Local(mut local) => {
+            local.init = local.init.map(|init| LocalInit {
+                expr: Box::new(value(*init.expr)),
+                ..init
+            });
+            Stmt::Local(local)
+        }
+        Stmt::Expr(Expr::Assign(mut assign), semi) => {
+            assign.right = Box::new(value(*assign.right));
+            Stmt::Expr(Expr::Assign(assign), semi)
+        }
+        Stmt::Expr(Expr::Binary(mut binary), semi) if is_assignment(&binary.op) => {
+            binary.right = Box::new(value(*binary.right));
+            Stmt::Expr(Expr::Binary(binary), semi)
+        }
+        Stmt::Expr(expr, None) => Stmt::Expr(operand(expr), None),
+        statement => statement,
+    }
+}
+
+// `expr` as a `Checked`, even if it's a literal.
+fn value(expr: Expr) -> Expr {
+    lift(operand(expr))
+}
+
+// `expr` with all of its operators applied to `Checked` values. Each operand is lifted into a
+// `Checked` as a whole, so `v[i]`, `f(x)` and `x as u32` are evaluated as they are first.
+// Literals are left alone so their type can still be inferred from the other operand.
+fn operand(expr: Expr) -> Expr {
+    match expr {
+        Expr::Lit(_) => expr,
+        Expr::Binary(mut binary) if is_operator(&binary.op) => {
+            binary.left = Box::new(operand(*binary.left));
+            binary.right = Box::new(operand(*binary.right));
+            Expr::Binary(binary)
+        }
+        // Negative literals are fine as they are (and `-128_i8` has to stay a literal).
+        Expr::Unary(unary) if matches!(*unary.expr, Expr::Lit(_)) => Expr::Unary(unary),
+        Expr::Unary(mut unary) if matches!(unary.op, UnOp::Neg(_) | UnOp::Not(_)) => {
+            unary.expr = Box::new(operand(*unary.expr));
+            Expr::Unary(unary)
+        }
+        Expr::Paren(mut paren) => {
+            paren.expr = Box::new(operand(*paren.expr));
+            Expr::Paren(paren)
+        }
+        Expr::Group(mut group) => {
+            group.expr = Box::new(operand(*group.expr));
+            Expr::Group(group)
+        }
+        Expr::Block(mut block) if block.label.is_none() => {
+            block.block.stmts = statements(block.block.stmts);
+            Expr::Block(block)
+        }
+        expr => lift(expr),
+    }
+}
+
+fn lift(expr: Expr) -> Expr {
+    Expr::Verbatim(quote!(::checked::__private::lift(#expr)))
+}
+
+// The operators `Checked` has.
+fn is_operator(op: &BinOp) -> bool {
+    matches!(
+        *op,
+        BinOp::Add(_)
+            | BinOp::Sub(_)
+            | BinOp::Mul(_)
+            | BinOp::Div(_)
+            | BinOp::Rem(_)
+            | BinOp::Shl(_)
+            | BinOp::Shr(_)
+            | BinOp::BitAnd(_)
+            | BinOp::BitOr(_)
+            | BinOp::BitXor(_)
+    )
+}
+
+fn is_assignment(op: &BinOp) -> bool {
+    matches!(
+        *op,
+        BinOp::AddAssign(_)
+            | BinOp::SubAssign(_)
+            | BinOp::MulAssign(_)
+            | BinOp::DivAssign(_)
+            | BinOp::RemAssign(_)
+            | BinOp::ShlAssign(_)
+            | BinOp::ShrAssign(_)
+            | BinOp::BitAndAssign(_)
+            | BinOp::BitOrAssign(_)
+            | BinOp::BitXorAssign(_)
+    )
+}
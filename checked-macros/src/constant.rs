@@ -0,0 +1,98 @@
+use proc_macro2::{Span, TokenStream};
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::{Expr, Ident, Type, UnOp};
+
+use attr::binary_method;
+
+pub struct Input {
+    ty: Type,
+    expr: Expr,
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream) -> syn::Result<Input> {
+        let ty = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let expr = input.parse()?;
+        Ok(Input { ty, expr })
+    }
+}
+
+pub fn const_checked(input: Input) -> TokenStream {
+    let mut evaluator = Evaluator {
+        steps: Vec::new(),
+        u32: parse_quote!(u32),
+    };
+    let result = evaluator.eval(&input.expr, &input.ty);
+    let (ty, steps) = (&input.ty, &evaluator.steps);
+    // The inner constant makes sure this is evaluated at compile time, wherever it's used.
+    quote! {
+        {
+            const __CHECKED_VALUE: #ty = {
+                #(#steps)*
+                #result
+            };
+            __CHECKED_VALUE
+        }
+    }
+}
+
+// Turns an expression into a sequence of `let`s, one for each operator, each checking the result.
+struct Evaluator {
+    steps: Vec<TokenStream>,
+    u32: Type,
+}
+
+impl Evaluator {
+    // Adds the steps to evaluate `expr` as a `ty`, and returns the variable holding the result.
+    fn eval(&mut self, expr: &Expr, ty: &Type) -> Ident {
+        let value = match *expr {
+            Expr::Paren(ref paren) => return self.eval(&paren.expr, ty),
+            Expr::Group(ref group) => return self.eval(&group.expr, ty),
+            Expr::Binary(ref binary) => match binary_method(&binary.op) {
+                Some((method, false)) => {
+                    let const_method = Ident::new(&format!("const_{}", method), Span::call_site());
+                    let left = self.eval(&binary.left, ty);
+                    // Shift amounts are `u32`, as for `Checked`.
+                    let right = if method == "shl" || method == "shr" {
+                        let right = self.eval(&binary.right, &self.u32.clone());
+                        quote!(#right)
+                    } else {
+                        let right = self.eval(&binary.right, ty);
+                        quote!(::checked::Checked::new(#right))
+                    };
+                    let checked = quote!(::checked::Checked::<#ty>::new(#left).#const_method(#right));
+                    self.check(expr, binary.op.span(), checked)
+                }
+                _ => quote!(#expr),
+            },
+            // Negative literals are fine as they are (and `-128_i8` has to stay a literal).
+            Expr::Unary(ref unary) => match (&unary.op, &*unary.expr) {
+                (UnOp::Neg(_), Expr::Lit(_)) => quote!(#expr),
+                (UnOp::Neg(op), operand) => {
+                    let operand = self.eval(operand, ty);
+                    let checked = quote!(::checked::Checked::<#ty>::new(#operand).const_neg());
+                    self.check(expr, op.span, checked)
+                }
+                _ => quote!(#expr),
+            },
+            _ => quote!(#expr),
+        };
+        let var = Ident::new(&format!("__checked_{}", self.steps.len()), Span::call_site());
+        self.steps.push(quote!(let #var: #ty = #value;));
+        var
+    }
+
+    // Unwraps `checked`, failing the constant evaluation with a message naming `expr`.
+    fn check(&self, expr: &Expr, span: Span, checked: TokenStream) -> TokenStream {
+        let message = format!("arithmetic overflow in `{}`", quote!(#expr));
+        let span = Span::call_site().located_at(span);
+        quote_spanned! {span=>
+            match #checked.0 {
+                Some(__checked_value) => __checked_value,
+                None => panic!("{}", #message),
+            }
+        }
+    }
+}
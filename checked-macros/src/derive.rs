@@ -0,0 +1,226 @@
+use proc_macro2::TokenStream;
+use syn::{Attribute, Data, DeriveInput, Fields, Ident};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Neg,
+    Shl,
+    Shr,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Not,
+}
+
+impl Op {
+    pub const ALL: [Op; 12] = [
+        Op::Add,
+        Op::Sub,
+        Op::Mul,
+        Op::Div,
+        Op::Rem,
+        Op::Neg,
+        Op::Shl,
+        Op::Shr,
+        Op::BitAnd,
+        Op::BitOr,
+        Op::BitXor,
+        Op::Not,
+    ];
+
+    // The name of the operator trait, which is also what the attribute calls it.
+    pub fn name(self) -> &'static str {
+        match self {
+            Op::Add => "Add",
+            Op::Sub => "Sub",
+            Op::Mul => "Mul",
+            Op::Div => "Div",
+            Op::Rem => "Rem",
+            Op::Neg => "Neg",
+            Op::Shl => "Shl",
+            Op::Shr => "Shr",
+            Op::BitAnd => "BitAnd",
+            Op::BitOr => "BitOr",
+            Op::BitXor => "BitXor",
+            Op::Not => "Not",
+        }
+    }
+
+    pub fn method(self) -> &'static str {
+        match self {
+            Op::Add => "add",
+            Op::Sub => "sub",
+            Op::Mul => "mul",
+            Op::Div => "div",
+            Op::Rem => "rem",
+            Op::Neg => "neg",
+            Op::Shl => "shl",
+            Op::Shr => "shr",
+            Op::BitAnd => "bitand",
+            Op::BitOr => "bitor",
+            Op::BitXor => "bitxor",
+            Op::Not => "not",
+        }
+    }
+}
+
+// The operators asked for with `#[checked_ops(...)]`, or all of them.
+fn parse_ops(attrs: &[Attribute]) -> syn::Result<Vec<Op>> {
+    let mut ops = None;
+    for attr in attrs {
+        if !attr.path().is_ident("checked_ops") {
+            continue;
+        }
+        let list = ops.get_or_insert_with(Vec::new);
+        attr.parse_nested_meta(|meta| {
+            let op = Op::ALL
+                .iter()
+                .find(|op| meta.path.is_ident(op.name()))
+                .ok_or_else(|| {
+                    meta.error(
+                        "expected one of `Add`, `Sub`, `Mul`, `Div`, `Rem`, `Neg`, `Shl`, `Shr`, \
+                         `BitAnd`, `BitOr`, `BitXor` or `Not`",
+                    )
+                })?;
+            if !list.contains(op) {
+                list.push(*op);
+            }
+            Ok(())
+        })?;
+    }
+    Ok(ops.unwrap_or_else(|| Op::ALL.to_vec()))
+}
+
+pub fn checked_ops(input: DeriveInput) -> syn::Result<TokenStream> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "`CheckedOps` can't be derived for generic types",
+        ));
+    }
+    let fields = match input.data {
+        Data::Struct(ref data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "`CheckedOps` can only be derived for structs",
+            ))
+        }
+    };
+    match *fields {
+        Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => {}
+        _ => {
+            return Err(syn::Error::new_spanned(
+                fields,
+                "`CheckedOps` needs a newtype, a struct with exactly one unnamed field",
+            ))
+        }
+    }
+    let ops = parse_ops(&input.attrs)?;
+    let name = &input.ident;
+    Ok(ops.into_iter().map(|op| impl_op(name, op)).collect())
+}
+
+// The `checked::ops` trait for `op`, and the reverse forms of the operator.
+// The bitwise operators can't fail, so for them it's the operator itself on the newtype, which
+// `Checked` uses as it is.
+fn impl_op(name: &Ident, op: Op) -> TokenStream {
+    let imp = format_ident!("{}", op.name());
+    let method = format_ident!("{}", op.method());
+    let checked_trait = format_ident!("Checked{}", op.name());
+    let checked_method = format_ident!("checked_{}", op.method());
+    let checked = quote!(::checked::Checked);
+    let ops = quote!(::checked::__private::ops);
+    let option = quote!(::checked::__private::Option);
+
+    let (rhs, arg) = match op {
+        Op::Neg => {
+            return quote! {
+                impl ::checked::ops::#checked_trait for #name {
+                    #[inline]
+                    fn #checked_method(self) -> #option<#name> {
+                        #ops::#imp::#method(#checked::new(self.0)).0.map(#name)
+                    }
+                }
+            }
+        }
+        Op::Not => {
+            return quote! {
+                impl #ops::#imp for #name {
+                    type Output = #name;
+
+                    #[inline]
+                    fn #method(self) -> #name {
+                        #name(#ops::#imp::#method(self.0))
+                    }
+                }
+            }
+        }
+        Op::Shl | Op::Shr => (quote!(u32), quote!(other)),
+        _ => (quote!(#name), quote!(other.0)),
+    };
+    let operation = match op {
+        Op::BitAnd | Op::BitOr | Op::BitXor => quote! {
+            impl #ops::#imp for #name {
+                type Output = #name;
+
+                #[inline]
+                fn #method(self, other: #name) -> #name {
+                    #name(#ops::#imp::#method(self.0, other.0))
+                }
+            }
+        },
+        _ => quote! {
+            impl ::checked::ops::#checked_trait for #name {
+                #[inline]
+                fn #checked_method(self, other: #rhs) -> #option<#name> {
+                    #ops::#imp::#method(#checked::new(self.0), #arg).0.map(#name)
+                }
+            }
+        },
+    };
+    quote! {
+        #operation
+
+        impl #ops::#imp<#checked<#rhs>> for #name {
+            type Output = #checked<#name>;
+
+            #[inline]
+            fn #method(self, other: #checked<#rhs>) -> #checked<#name> {
+                #ops::#imp::#method(#checked::new(self), other)
+            }
+        }
+
+        impl<'a> #ops::#imp<&'a #checked<#rhs>> for #name {
+            type Output = #checked<#name>;
+
+            #[inline]
+            fn #method(self, other: &'a #checked<#rhs>) -> #checked<#name> {
+                #ops::#imp::#method(#checked::new(self), *other)
+            }
+        }
+
+        impl<'a> #ops::#imp<#checked<#rhs>> for &'a #name {
+            type Output = #checked<#name>;
+
+            #[inline]
+            fn #method(self, other: #checked<#rhs>) -> #checked<#name> {
+                #ops::#imp::#method(#checked::new(*self), other)
+            }
+        }
+
+        impl<'a, 'b> #ops::#imp<&'a #checked<#rhs>> for &'b #name {
+            type Output = #checked<#name>;
+
+            #[inline]
+            fn #method(self, other: &'a #checked<#rhs>) -> #checked<#name> {
+                #ops::#imp::#method(#checked::new(*self), *other)
+            }
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use proc_macro2::{Span, TokenStream};
+use syn::spanned::Spanned;
+use syn::visit_mut::{self, VisitMut};
+use syn::{Expr, GenericArgument, Ident, Item, UnOp};
+
+use attr::{binary_method, unparen};
+
+pub fn checked_expr(expr: Expr) -> syn::Result<TokenStream> {
+    let mut evaluator = Evaluator {
+        label: syn::Lifetime::new("'__checked_expr", Span::mixed_site()),
+        error: None,
+    };
+    let result = evaluator.eval(&expr);
+    if let Some(error) = evaluator.error {
+        return Err(error);
+    }
+    let label = &evaluator.label;
+    Ok(quote! {
+        #label: {
+            ::checked::__private::Ok::<_, ::checked::ExprError>(#result)
+        }
+    })
+}
+
+// Turns each operator of an expression into a block that breaks out with the error if it fails.
+// The blocks stay where the operators were, so everything is evaluated in the same order, and
+// only if it would have been.
+struct Evaluator {
+    label: syn::Lifetime,
+    error: Option<syn::Error>,
+}
+
+impl Evaluator {
+    // The code to evaluate `expr`. Anything that isn't an operator can still have them inside it,
+    // as in `(x * y) & mask` or `v[i + 1]`, so this goes through the whole expression.
+    fn eval(&mut self, expr: &Expr) -> TokenStream {
+        let mut expr = expr.clone();
+        self.visit_expr_mut(&mut expr);
+        quote!(#expr)
+    }
+
+    // The code for `expr` if it's one of the operators that `Checked` has.
+    fn eval_operator(&mut self, expr: &Expr) -> Option<TokenStream> {
+        match *expr {
+            Expr::Binary(ref binary) => match binary_method(&binary.op) {
+                Some((method, false)) => {
+                    let left = self.eval(unparen(&binary.left));
+                    let right = self.eval(unparen(&binary.right));
+                    Some(self.check(expr, method, left, Some(right)))
+                }
+                Some((_, true)) => {
+                    self.fail(
+                        binary.op.span(),
+                        "`checked_expr!` can't check compound assignments; use `#[checked]` on the function instead",
+                    );
+                    None
+                }
+                None => None,
+            },
+            // Negative literals are fine as they are (and `-128_i8` has to stay a literal).
+            Expr::Unary(ref unary) => match (&unary.op, &*unary.expr) {
+                (UnOp::Neg(_), Expr::Lit(_)) => None,
+                (UnOp::Neg(_), operand) => {
+                    let operand = self.eval(unparen(operand));
+                    Some(self.check(expr, "neg", operand, None))
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn fail(&mut self, span: Span, message: &str) {
+        let error = syn::Error::new(span, message);
+        match self.error {
+            Some(ref mut errors) => errors.combine(error),
+            None => self.error = Some(error),
+        }
+    }
+
+    // Applies the `Checked` operator, breaking out with the error if it fails.
+    fn check(
+        &self,
+        expr: &Expr,
+        method: &str,
+        left: TokenStream,
+        right: Option<TokenStream>,
+    ) -> TokenStream {
+        let text = quote!(#expr).to_string();
+        let label = &self.label;
+        let private = quote!(::checked::__private);
+        let imp = Ident::new(&capitalize(method), Span::call_site());
+        let method = Ident::new(method, Span::call_site());
+        let op = quote!(::checked::Op::#imp);
+        let lhs = Ident::new("__checked_lhs", Span::mixed_site());
+        let rhs = Ident::new("__checked_rhs", Span::mixed_site());
+        // Like the primitive operators, this evaluates the left operand first.
+        let (operands, result, rhs_value) = match right {
+            Some(right) => (
+                quote!(let #lhs = #left; let #rhs = #right;),
+                quote!(#private::ops::#imp::#method(::checked::Checked::new(#lhs), #rhs)),
+                quote!(#private::Some(::checked::Value::from(#rhs))),
+            ),
+            None => (
+                quote!(let #lhs = #left;),
+                quote!(#private::ops::#imp::#method(::checked::Checked::new(#lhs))),
+                quote!(#private::None),
+            ),
+        };
+        quote! {
+            {
+                #operands
+                match #result.0 {
+                    #private::Some(__checked_value) => __checked_value,
+                    #private::None => {
+                        let lhs = ::checked::Value::from(#lhs);
+                        let rhs = #rhs_value;
+                        break #label #private::Err(::checked::ExprError {
+                            expr: #text,
+                            op: #op,
+                            lhs,
+                            rhs,
+                            reason: ::checked::Reason::of(#op, lhs, rhs),
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl VisitMut for Evaluator {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        match *expr {
+            // There's no breaking out of these, and macro arguments can't be looked into.
+            Expr::Closure(_) | Expr::Async(_) | Expr::Const(_) | Expr::Macro(_) => return,
+            // The length has to stay a constant.
+            Expr::Repeat(ref mut repeat) => return self.visit_expr_mut(&mut repeat.expr),
+            _ => {}
+        }
+        match self.eval_operator(expr) {
+            Some(checked) => *expr = Expr::Verbatim(checked),
+            None => visit_mut::visit_expr_mut(self, expr),
+        }
+    }
+
+    // Types, generic arguments and nested items are their own business.
+    fn visit_type_mut(&mut self, _: &mut syn::Type) {}
+
+    fn visit_generic_argument_mut(&mut self, _: &mut GenericArgument) {}
+
+    fn visit_item_mut(&mut self, _: &mut Item) {}
+}
+
+fn capitalize(method: &str) -> String {
+    let mut name = method[..1].to_uppercase();
+    name.push_str(&method[1..]);
+    name
+}
@@ -0,0 +1,464 @@
+use proc_macro2::TokenStream;
+use syn::punctuated::Punctuated;
+use syn::{token, Attribute, Data, DeriveInput, Fields, Ident, Member, Type};
+
+use derive::Op;
+
+// An operator to implement: component-wise with the struct itself, or with a scalar on the right.
+struct Spec {
+    op: Op,
+    scalar: Option<Type>,
+}
+
+// The operators asked for with `#[checked_fields(...)]`.
+// Without the attribute, that's `+` and `-`, and if all the fields have the same type,
+// `*` and `/` by that type.
+fn parse_specs(attrs: &[Attribute], fields: &Fields) -> syn::Result<Vec<Spec>> {
+    let mut specs = None;
+    for attr in attrs {
+        if !attr.path().is_ident("checked_fields") {
+            continue;
+        }
+        let list = specs.get_or_insert_with(Vec::new);
+        attr.parse_nested_meta(|meta| {
+            let op = *Op::ALL
+                .iter()
+                .find(|op| meta.path.is_ident(op.name()))
+                .ok_or_else(|| {
+                    meta.error("expected one of `Add`, `Sub`, `Mul`, `Div`, `Rem`, `Neg`, `Shl` or `Shr`")
+                })?;
+            if meta.input.peek(token::Paren) {
+                if op == Op::Neg {
+                    return Err(meta.error("`Neg` doesn't take a scalar type"));
+                }
+                let content;
+                parenthesized!(content in meta.input);
+                let scalars = Punctuated::<Type, Token![,]>::parse_terminated(&content)?;
+                list.extend(scalars.into_iter().map(|scalar| Spec {
+                    op,
+                    scalar: Some(scalar),
+                }));
+            } else {
+                if op == Op::Shl || op == Op::Shr {
+                    return Err(meta.error("shifts need the type of the amount, as in `Shl(u32)`"));
+                }
+                list.push(Spec { op, scalar: None });
+            }
+            Ok(())
+        })?;
+    }
+    if let Some(specs) = specs {
+        return Ok(specs);
+    }
+    let mut specs = vec![
+        Spec {
+            op: Op::Add,
+            scalar: None,
+        },
+        Spec {
+            op: Op::Sub,
+            scalar: None,
+        },
+    ];
+    let mut types = fields.iter().map(|field| &field.ty);
+    if let Some(first) = types.next() {
+        let text = quote!(#first).to_string();
+        if types.all(|ty| quote!(#ty).to_string() == text) {
+            for &op in &[Op::Mul, Op::Div] {
+                specs.push(Spec {
+                    op,
+                    scalar: Some(first.clone()),
+                });
+            }
+        }
+    }
+    Ok(specs)
+}
+
+pub fn checked_fields(input: DeriveInput) -> syn::Result<TokenStream> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "`CheckedFields` can't be derived for generic types",
+        ));
+    }
+    let fields = match input.data {
+        Data::Struct(ref data) if !data.fields.is_empty() => &data.fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "`CheckedFields` can only be derived for structs with fields",
+            ))
+        }
+    };
+    let specs = parse_specs(&input.attrs, fields)?;
+    let generator = Generator {
+        name: &input.ident,
+        checked_name: format_ident!("Checked{}", input.ident),
+        members: fields.members().collect(),
+    };
+    let mut out = generator.definition(&input.vis);
+    for spec in specs {
+        out.extend(match (spec.op, spec.scalar) {
+            (Op::Neg, _) => generator.neg(),
+            (op, None) => generator.componentwise(op),
+            (op, Some(scalar)) => generator.scalar(op, &scalar),
+        });
+    }
+    Ok(out)
+}
+
+struct Generator<'a> {
+    name: &'a Ident,
+    checked_name: Ident,
+    members: Vec<Member>,
+}
+
+impl<'a> Generator<'a> {
+    // The type itself, and everything but the operators.
+    fn definition(&self, vis: &syn::Visibility) -> TokenStream {
+        let (name, checked_name) = (self.name, &self.checked_name);
+        let private = quote!(::checked::__private);
+        let doc = format!(
+            "`{}` with checked arithmetic on each of its fields, failing as a whole.\n\n\
+             This is generated by `#[derive(CheckedFields)]`, and works like `checked::Checked`.",
+            name
+        );
+        quote! {
+            #[doc = #doc]
+            #[derive(#private::Clone, #private::Copy)]
+            #vis struct #checked_name(pub #private::Option<#name>);
+
+            impl #checked_name {
+                /// Creates a new value from a valid one.
+                #[inline]
+                pub const fn new(x: #name) -> #checked_name {
+                    #checked_name(#private::Some(x))
+                }
+
+                /// The failure state.
+                pub const OVERFLOW: #checked_name = #checked_name(#private::None);
+            }
+
+            impl #private::Default for #checked_name {
+                #[inline]
+                fn default() -> #checked_name {
+                    #checked_name(#private::None)
+                }
+            }
+
+            impl #private::ops::Deref for #checked_name {
+                type Target = #private::Option<#name>;
+
+                #[inline]
+                fn deref(&self) -> &#private::Option<#name> {
+                    &self.0
+                }
+            }
+
+            impl #private::ops::DerefMut for #checked_name {
+                #[inline]
+                fn deref_mut(&mut self) -> &mut #private::Option<#name> {
+                    &mut self.0
+                }
+            }
+
+            impl #private::From<#name> for #checked_name {
+                #[inline]
+                fn from(x: #name) -> #checked_name {
+                    #checked_name(#private::Some(x))
+                }
+            }
+
+            impl #private::From<#private::Option<#name>> for #checked_name {
+                #[inline]
+                fn from(x: #private::Option<#name>) -> #checked_name {
+                    #checked_name(x)
+                }
+            }
+
+            impl #private::From<::checked::Checked<#name>> for #checked_name {
+                #[inline]
+                fn from(x: ::checked::Checked<#name>) -> #checked_name {
+                    #checked_name(x.0)
+                }
+            }
+
+            impl #private::From<#checked_name> for ::checked::Checked<#name> {
+                #[inline]
+                fn from(x: #checked_name) -> ::checked::Checked<#name> {
+                    ::checked::Checked(x.0)
+                }
+            }
+
+            // The bounds are on references under `for<'a>` so that they're only checked where the
+            // impls are used: the struct doesn't have to be `Debug` or `PartialEq` itself.
+            impl #private::fmt::Debug for #checked_name
+            where
+                for<'a> &'a #name: #private::fmt::Debug,
+            {
+                fn fmt(&self, f: &mut #private::fmt::Formatter) -> #private::fmt::Result {
+                    match self.0 {
+                        #private::Some(ref x) => #private::fmt::Debug::fmt(&x, f),
+                        #private::None => #private::fmt::Debug::fmt("overflow", f),
+                    }
+                }
+            }
+
+            impl #private::PartialEq for #checked_name
+            where
+                for<'a> &'a #name: #private::PartialEq,
+            {
+                #[inline]
+                fn eq(&self, other: &#checked_name) -> bool {
+                    self.0.as_ref() == other.0.as_ref()
+                }
+            }
+
+            impl #private::Eq for #checked_name where for<'a> &'a #name: #private::Eq {}
+        }
+    }
+
+    // Builds the struct from an expression for each field, each giving a `Checked`.
+    fn combine<F: Fn(&Member) -> TokenStream>(&self, field: F) -> TokenStream {
+        let private = quote!(::checked::__private);
+        let (name, checked_name, members) = (self.name, &self.checked_name, &self.members);
+        let values = members.iter().map(|member| {
+            let value = field(member);
+            quote!((#value).0)
+        });
+        let vars: Vec<_> = (0..members.len())
+            .map(|i| format_ident!("__checked_{}", i))
+            .collect();
+        quote! {
+            match (#(#values,)*) {
+                (#(#private::Some(#vars),)*) => #checked_name(#private::Some(#name { #(#members: #vars),* })),
+                _ => #checked_name(#private::None),
+            }
+        }
+    }
+
+    // `op` applied field by field, with the struct (or the checked type) on either side.
+    fn componentwise(&self, op: Op) -> TokenStream {
+        let private = quote!(::checked::__private);
+        let (name, checked_name) = (self.name, &self.checked_name);
+        let (imp, method, imp_assign, method_assign) = idents(op);
+        let ops = quote!(::checked::__private::ops);
+        let combined = self.combine(|member| {
+            quote!(#ops::#imp::#method(::checked::Checked::new(__checked_value.#member), other.#member))
+        });
+        let this = quote!(#checked_name);
+        let plain = quote!(#name);
+        let forward = [
+            forward_ref(&imp, &method, &this, &this),
+            forward_ref(&imp, &method, &this, &plain),
+            forward_ref(&imp, &method, &plain, &this),
+        ];
+        quote! {
+            impl #ops::#imp<#name> for #checked_name {
+                type Output = #checked_name;
+
+                fn #method(self, other: #name) -> #checked_name {
+                    match self.0 {
+                        #private::Some(__checked_value) => #combined,
+                        #private::None => #checked_name(#private::None),
+                    }
+                }
+            }
+
+            impl #ops::#imp for #checked_name {
+                type Output = #checked_name;
+
+                fn #method(self, other: #checked_name) -> #checked_name {
+                    match other.0 {
+                        #private::Some(other) => #ops::#imp::#method(self, other),
+                        #private::None => #checked_name(#private::None),
+                    }
+                }
+            }
+
+            impl #ops::#imp<#checked_name> for #name {
+                type Output = #checked_name;
+
+                #[inline]
+                fn #method(self, other: #checked_name) -> #checked_name {
+                    #ops::#imp::#method(#checked_name::new(self), other)
+                }
+            }
+
+            #(#forward)*
+
+            impl #ops::#imp_assign<#name> for #checked_name {
+                #[inline]
+                fn #method_assign(&mut self, other: #name) {
+                    *self = #ops::#imp::#method(*self, other);
+                }
+            }
+
+            impl #ops::#imp_assign for #checked_name {
+                #[inline]
+                fn #method_assign(&mut self, other: #checked_name) {
+                    *self = #ops::#imp::#method(*self, other);
+                }
+            }
+        }
+    }
+
+    // `op` with the same scalar (or checked scalar) for every field.
+    // `+` and `*` also get the scalar on the left.
+    fn scalar(&self, op: Op, scalar: &Type) -> TokenStream {
+        let private = quote!(::checked::__private);
+        let checked_name = &self.checked_name;
+        let (imp, method, imp_assign, method_assign) = idents(op);
+        let ops = quote!(::checked::__private::ops);
+        let combined = self.combine(|member| {
+            quote!(#ops::#imp::#method(::checked::Checked::new(__checked_value.#member), other))
+        });
+        let this = quote!(#checked_name);
+        let plain = quote!(#scalar);
+        let checked = quote!(::checked::Checked<#scalar>);
+        let mut forward = vec![
+            forward_ref(&imp, &method, &this, &plain),
+            forward_ref(&imp, &method, &this, &checked),
+        ];
+        let reverse = if op == Op::Add || op == Op::Mul {
+            forward.push(forward_ref(&imp, &method, &plain, &this));
+            forward.push(forward_ref(&imp, &method, &checked, &this));
+            quote! {
+                impl #ops::#imp<#checked_name> for #scalar {
+                    type Output = #checked_name;
+
+                    #[inline]
+                    fn #method(self, other: #checked_name) -> #checked_name {
+                        #ops::#imp::#method(other, self)
+                    }
+                }
+
+                impl #ops::#imp<#checked_name> for ::checked::Checked<#scalar> {
+                    type Output = #checked_name;
+
+                    #[inline]
+                    fn #method(self, other: #checked_name) -> #checked_name {
+                        #ops::#imp::#method(other, self)
+                    }
+                }
+            }
+        } else {
+            TokenStream::new()
+        };
+        quote! {
+            impl #ops::#imp<#scalar> for #checked_name {
+                type Output = #checked_name;
+
+                fn #method(self, other: #scalar) -> #checked_name {
+                    match self.0 {
+                        #private::Some(__checked_value) => #combined,
+                        #private::None => #checked_name(#private::None),
+                    }
+                }
+            }
+
+            impl #ops::#imp<::checked::Checked<#scalar>> for #checked_name {
+                type Output = #checked_name;
+
+                fn #method(self, other: ::checked::Checked<#scalar>) -> #checked_name {
+                    match other.0 {
+                        #private::Some(other) => #ops::#imp::#method(self, other),
+                        #private::None => #checked_name(#private::None),
+                    }
+                }
+            }
+
+            #reverse
+
+            #(#forward)*
+
+            impl #ops::#imp_assign<#scalar> for #checked_name {
+                #[inline]
+                fn #method_assign(&mut self, other: #scalar) {
+                    *self = #ops::#imp::#method(*self, other);
+                }
+            }
+
+            impl #ops::#imp_assign<::checked::Checked<#scalar>> for #checked_name {
+                #[inline]
+                fn #method_assign(&mut self, other: ::checked::Checked<#scalar>) {
+                    *self = #ops::#imp::#method(*self, other);
+                }
+            }
+        }
+    }
+
+    fn neg(&self) -> TokenStream {
+        let private = quote!(::checked::__private);
+        let checked_name = &self.checked_name;
+        let ops = quote!(::checked::__private::ops);
+        let combined = self.combine(|member| {
+            quote!(#ops::Neg::neg(::checked::Checked::new(__checked_value.#member)))
+        });
+        quote! {
+            impl #ops::Neg for #checked_name {
+                type Output = #checked_name;
+
+                fn neg(self) -> #checked_name {
+                    match self.0 {
+                        #private::Some(__checked_value) => #combined,
+                        #private::None => #checked_name(#private::None),
+                    }
+                }
+            }
+
+            impl<'a> #ops::Neg for &'a #checked_name {
+                type Output = #checked_name;
+
+                #[inline]
+                fn neg(self) -> #checked_name {
+                    #ops::Neg::neg(*self)
+                }
+            }
+        }
+    }
+}
+
+// The operator trait and method, and the assignment ones.
+fn idents(op: Op) -> (Ident, Ident, Ident, Ident) {
+    (
+        format_ident!("{}", op.name()),
+        format_ident!("{}", op.method()),
+        format_ident!("{}Assign", op.name()),
+        format_ident!("{}_assign", op.method()),
+    )
+}
+
+// "&T op U", "T op &U" and "&T op &U" based on "T op U", like `forward_ref_binop!` in `checked`.
+fn forward_ref(imp: &Ident, method: &Ident, lhs: &TokenStream, rhs: &TokenStream) -> TokenStream {
+    let ops = quote!(::checked::__private::ops);
+    quote! {
+        impl<'a> #ops::#imp<#rhs> for &'a #lhs {
+            type Output = <#lhs as #ops::#imp<#rhs>>::Output;
+
+            #[inline]
+            fn #method(self, other: #rhs) -> <#lhs as #ops::#imp<#rhs>>::Output {
+                #ops::#imp::#method(*self, other)
+            }
+        }
+
+        impl<'a> #ops::#imp<&'a #rhs> for #lhs {
+            type Output = <#lhs as #ops::#imp<#rhs>>::Output;
+
+            #[inline]
+            fn #method(self, other: &'a #rhs) -> <#lhs as #ops::#imp<#rhs>>::Output {
+                #ops::#imp::#method(self, *other)
+            }
+        }
+
+        impl<'a, 'b> #ops::#imp<&'a #rhs> for &'b #lhs {
+            type Output = <#lhs as #ops::#imp<#rhs>>::Output;
+
+            #[inline]
+            fn #method(self, other: &'a #rhs) -> <#lhs as #ops::#imp<#rhs>>::Output {
+                #ops::#imp::#method(*self, *other)
+            }
+        }
+    }
+}
@@ -0,0 +1,300 @@
+//! Procedural macros for the [`checked`](https://docs.rs/checked) crate.
+//!
+//! Use them through `checked` with its `macros` feature enabled, rather than depending on this
+//! crate directly: the code they generate refers to `checked`.
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+#[macro_use]
+extern crate syn;
+
+mod attr;
+mod block;
+mod constant;
+mod derive;
+mod expr;
+mod fields;
+
+use proc_macro::TokenStream;
+
+/// Makes all the integer arithmetic in a function checked.
+///
+/// Every `+`, `-`, `*`, `/`, `%`, `<<`, `>>` and unary `-` in the body (and the assignment
+/// versions of them) goes through the `Checked` operators.
+/// The values stay plain integers; what happens when an operation fails is chosen with the
+/// argument:
+///
+/// - `#[checked]` or `#[checked(panic)]` panics, naming the expression, at its location.
+/// - `#[checked(option)]` returns `None` from the function.
+/// - `#[checked(result = <error>)]` returns `Err(From::from(<error>))` from the function.
+///
+/// Closures, nested items and macro invocations aren't rewritten, since an early return there
+/// would leave the wrong function. Shift amounts have to be `u32`, as with `Checked`.
+/// Float arithmetic is left as it is.
+///
+/// The attribute can't see types, so it rewrites every operator, and operators on anything but
+/// integers and floats don't compile. Those with a string, character or `bool` literal operand,
+/// as in `name + "s"`, are left alone; others, such as `Duration` or `Wrapping` arithmetic, can
+/// be wrapped in [`unchecked!`](../macro.unchecked.html). Constants in types and generic
+/// arguments, such as the `N * 2` in `[u8; N * 2]`, aren't rewritten either.
+/// # Examples
+/// ```
+/// extern crate checked;
+/// use checked::attr::checked;
+///
+/// #[checked(option)]
+/// fn frame_size(width: u32, height: u32, bytes_per_pixel: u32) -> Option<u32> {
+///     Some(width * height * bytes_per_pixel)
+/// }
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Error {
+///     TooBig,
+/// }
+///
+/// #[checked(result = Error::TooBig)]
+/// fn total(counts: &[u16]) -> Result<u16, Error> {
+///     let mut total = 0;
+///     for count in counts {
+///         total += count;
+///     }
+///     Ok(total)
+/// }
+///
+/// # fn main() {
+/// assert_eq!(frame_size(1920, 1080, 4), Some(8_294_400));
+/// assert_eq!(frame_size(100_000, 100_000, 4), None);
+/// assert_eq!(total(&[1, 2, 3]), Ok(6));
+/// assert_eq!(total(&[60_000, 6_000]), Err(Error::TooBig));
+/// # }
+/// ```
+///
+/// ```should_panic
+/// extern crate checked;
+/// use checked::attr::checked;
+///
+/// #[checked]
+/// fn area(width: u8, height: u8) -> u8 {
+///     width * height
+/// }
+///
+/// # fn main() {
+/// area(16, 16); // panics with "arithmetic overflow in `width * height`"
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn checked(args: TokenStream, item: TokenStream) -> TokenStream {
+    attr::checked(args.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Gives `Checked` the operators for an integer newtype.
+///
+/// This implements the traits in `checked::ops` for a struct with a single unnamed field, in terms
+/// of `Checked` arithmetic on that field, so `Checked<Bytes>` gets the same operators as
+/// `Checked<u64>`:
+/// `Checked<Bytes> + Checked<Bytes>`, `Checked<Bytes> + Bytes`, `+=`, the reverse form
+/// `Bytes + Checked<Bytes>` and the reference forms.
+/// The shifts are by `u32`, as with the primitives.
+/// The bitwise operators can't fail, so for those the newtype itself gets `BitAnd`, `BitOr`,
+/// `BitXor` or `Not`, which `Checked<Bytes>` then has as well.
+/// The newtype has to be `Copy`.
+///
+/// By default every operator is implemented. To pick them, list them in a `checked_ops` attribute:
+/// any of `Add`, `Sub`, `Mul`, `Div`, `Rem`, `Neg`, `Shl`, `Shr`, `BitAnd`, `BitOr`, `BitXor`
+/// and `Not`.
+/// # Examples
+/// ```
+/// extern crate checked;
+/// use checked::{Checked, CheckedOps};
+///
+/// #[derive(CheckedOps, Clone, Copy, Debug, PartialEq)]
+/// #[checked_ops(Add, Sub, Div, Rem)]
+/// struct Bytes(u64);
+///
+/// # fn main() {
+/// let mut total = Checked::new(Bytes(0));
+/// total += Bytes(512);
+/// total = Bytes(512) + total;
+/// assert_eq!(total, Checked::new(Bytes(1024)));
+/// assert_eq!(total - Bytes(2048), Checked::OVERFLOW);
+/// # }
+/// ```
+///
+/// Operators that weren't asked for don't exist:
+///
+/// ```compile_fail
+/// extern crate checked;
+/// use checked::{Checked, CheckedOps};
+///
+/// #[derive(CheckedOps, Clone, Copy)]
+/// #[checked_ops(Add, Sub)]
+/// struct Bytes(u64);
+///
+/// # fn main() {
+/// let square = Checked::new(Bytes(2)) * Bytes(2);
+/// # }
+/// ```
+#[proc_macro_derive(CheckedOps, attributes(checked_ops))]
+pub fn checked_ops(input: TokenStream) -> TokenStream {
+    derive::checked_ops(parse_macro_input!(input))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates a checked version of a struct of integers, with component-wise operators.
+///
+/// For `struct Size`, this defines `CheckedSize`, which is to `Size` what `Checked<u32>` is to
+/// `u32`: it holds an `Option<Size>` and dereferences to it.
+/// Its operators apply `Checked` arithmetic to each field, and the result fails as a whole if
+/// any of the fields does.
+/// The fields can be of any type with `Checked` arithmetic: primitive integers, or newtypes with
+/// `#[derive(CheckedOps)]`. The struct has to be `Copy`.
+/// `CheckedSize` is `Debug`, `PartialEq` and `Eq` when `Size` is, and converts to and from
+/// `Checked<Size>`.
+///
+/// The operators are chosen with a `checked_fields` attribute:
+///
+/// - `Add`, `Sub`, `Mul`, `Div` and `Rem` apply the operator to each pair of fields, as in
+///   `CheckedSize + Size`.
+/// - `Neg` negates each field.
+/// - An operator with types, as in `Mul(u32)`, applies it with a scalar of each type to each
+///   field, as in `CheckedSize * u32`. For `Add` and `Mul`, the scalar can be on the left as well.
+///   The shifts only come in this form, as in `Shl(u32)`.
+///
+/// Without the attribute, the struct gets `Add` and `Sub`, and if all its fields have the same
+/// type, `Mul` and `Div` by that type.
+/// All of them come with the checked and reference forms, and the assignment operators.
+/// # Examples
+/// ```
+/// extern crate checked;
+/// use checked::CheckedFields;
+///
+/// #[derive(CheckedFields, Clone, Copy, Debug, PartialEq)]
+/// struct Size {
+///     w: u32,
+///     h: u32,
+/// }
+///
+/// # fn main() {
+/// let size = CheckedSize::new(Size { w: 640, h: 480 });
+/// assert_eq!(size * 2 + Size { w: 0, h: 60 }, CheckedSize::new(Size { w: 1280, h: 1020 }));
+/// assert_eq!(size - Size { w: 0, h: 481 }, CheckedSize::OVERFLOW);
+/// assert_eq!(size * u32::MAX, CheckedSize::OVERFLOW);
+/// # }
+/// ```
+///
+/// ```
+/// extern crate checked;
+/// use checked::{Checked, CheckedFields};
+///
+/// #[derive(CheckedFields, Clone, Copy, Debug, PartialEq)]
+/// #[checked_fields(Add, Sub, Neg, Mul(i32), Shl(u32))]
+/// struct Offset(i32, i32);
+///
+/// # fn main() {
+/// let offset = CheckedOffset::new(Offset(-3, 4));
+/// assert_eq!(-offset << 2, CheckedOffset::new(Offset(12, -16)));
+/// assert_eq!(Checked::new(2) * offset, CheckedOffset::new(Offset(-6, 8)));
+/// assert_eq!(offset + Offset(i32::MIN, 0), CheckedOffset::OVERFLOW);
+/// # }
+/// ```
+#[proc_macro_derive(CheckedFields, attributes(checked_fields))]
+pub fn checked_fields(input: TokenStream) -> TokenStream {
+    fields::checked_fields(parse_macro_input!(input))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Evaluates a constant expression with `Checked` arithmetic, at compile time.
+///
+/// `const_checked!(u32: 1 << 20 * 4096)` evaluates the expression as a `u32`, with the `+`, `-`,
+/// `*`, `/`, `%`, `<<`, `>>` and unary `-` checked, and gives the result as a plain `u32`.
+/// If any of them fails, that's a compile error naming the part of the expression that failed.
+/// Everything else in the expression, such as constants and `const fn` calls, is evaluated as it
+/// is, and has to be a constant.
+/// Shift amounts are `u32`, as with `Checked`.
+/// # Examples
+/// ```
+/// extern crate checked;
+/// use checked::const_checked;
+///
+/// const PAGE: u32 = 4096;
+/// const BUFFER: u32 = const_checked!(u32: (1 << 10) * PAGE);
+///
+/// # fn main() {
+/// assert_eq!(BUFFER, 4 * 1024 * 1024);
+/// # }
+/// ```
+///
+/// This one fails to compile with "arithmetic overflow in `1 << 20 * 4096`":
+///
+/// ```compile_fail
+/// extern crate checked;
+/// use checked::const_checked;
+///
+/// const BUFFER: u32 = const_checked!(u32: 1 << 20 * 4096);
+/// # fn main() {}
+/// ```
+#[proc_macro]
+pub fn const_checked(input: TokenStream) -> TokenStream {
+    constant::const_checked(parse_macro_input!(input)).into()
+}
+
+/// Evaluates an expression with the `Checked` operators, saying which part failed.
+///
+/// `checked_expr!(header_len + count * entry_size)` gives a `Result` with the value of the
+/// expression, or a `checked::ExprError` for the first operator that fails, with the failing
+/// sub-expression as text, the values of its operands and the reason.
+/// The operators are `+`, `-`, `*`, `/`, `%`, `<<`, `>>` and unary `-`, wherever they are in the
+/// expression: under other operators, in casts, call arguments, indexes and blocks. Only closures,
+/// `async` and `const` blocks and macro invocations inside it are evaluated as they are, since
+/// they can't report the error. Compound assignments such as `+=` are a compile error.
+/// The operands have to be primitive integers, and shift amounts `u32`, as with `Checked`.
+/// # Examples
+/// ```
+/// extern crate checked;
+/// use checked::{checked_expr, Op, Reason, Value};
+///
+/// # fn main() {
+/// let (header_len, count, entry_size) = (64_u32, 3_000_000_u32, 4096_u32);
+/// let error = checked_expr!(header_len + count * entry_size).unwrap_err();
+/// assert_eq!(error.expr, "count * entry_size");
+/// assert_eq!(error.op, Op::Mul);
+/// assert_eq!((error.lhs, error.rhs), (Value::from(count), Some(Value::from(entry_size))));
+/// assert_eq!(error.reason, Reason::PositiveOverflow);
+/// assert_eq!(
+///     error.to_string(),
+///     "positive overflow in `count * entry_size`: 3000000 * 4096",
+/// );
+///
+/// assert_eq!(checked_expr!(header_len + count / entry_size), Ok(796));
+/// # }
+/// ```
+///
+/// This one fails to compile, since the `+=` couldn't report its error:
+///
+/// ```compile_fail
+/// extern crate checked;
+/// use checked::checked_expr;
+///
+/// # fn main() {
+/// let mut total = 0_u32;
+/// let _ = checked_expr!(total += 1);
+/// # }
+/// ```
+#[proc_macro]
+pub fn checked_expr(input: TokenStream) -> TokenStream {
+    expr::checked_expr(parse_macro_input!(input))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+// The `checked!` macro of `checked`, where it's documented.
+#[doc(hidden)]
+#[proc_macro]
+pub fn checked_block(input: TokenStream) -> TokenStream {
+    block::checked_block(parse_macro_input!(input)).into()
+}
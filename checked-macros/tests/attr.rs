@@ -0,0 +1,195 @@
+#[macro_use]
+extern crate checked;
+
+use checked::attr::checked;
+use std::num::Wrapping;
+use std::time::Duration;
+
+#[checked(option)]
+fn polynomial(x: i32) -> Option<i32> {
+    Some(3 * x * x - 2 * x + 7)
+}
+
+#[checked(option)]
+fn shifts(x: u16, by: u32) -> Option<u16> {
+    Some((x << by) >> 1)
+}
+
+#[checked(option)]
+fn divide(x: u8, y: u8) -> Option<(u8, u8)> {
+    Some((x / y, x % y))
+}
+
+#[checked(option)]
+fn negate(x: i8) -> Option<i8> {
+    let offset = -1;
+    let min = -128;
+    Some(-x + offset).filter(|&y| y > min)
+}
+
+#[checked(option)]
+fn assignments(mut x: u32, v: &mut [u32]) -> Option<u32> {
+    x += 1;
+    x *= 2;
+    x -= 3;
+    x /= 1;
+    x %= 1_000_000_000;
+    x <<= 1;
+    x >>= 1;
+    v[x as usize % 2] += x;
+    Some(x)
+}
+
+#[checked(option)]
+fn untouched(x: u8, y: f64) -> Option<(u8, u8, f64, [u8; 2 * 2])> {
+    let wrap = |a: u8, b: u8| a.wrapping_add(b);
+    const BIG: u32 = 1 << 31;
+    Some((wrap(x, 255), x & 0xf0 | 3 ^ 1, y * 2.0 - 1.0, [0; 2 * 2]))
+        .filter(|_| BIG > 0)
+}
+
+const N: usize = 2;
+
+fn zeros<const M: usize>() -> [u8; M] {
+    [0; M]
+}
+
+#[checked(option)]
+fn constants(x: usize) -> Option<(usize, [u8; N * 2])> {
+    let buffer: [u8; N * 2] = zeros::<{ N * 2 }>();
+    let lengths = [0_usize; N + 1];
+    Some((x + buffer.len() + lengths.len(), buffer as [u8; N + N]))
+}
+
+#[checked(option)]
+fn other_types(name: String, x: u8, elapsed: Duration) -> Option<(String, u8, Duration, Wrapping<u8>)> {
+    let plural = name + "s";
+    let timeout = unchecked!(elapsed * 2 + Duration::from_secs(1));
+    let hash = unchecked!(Wrapping(x) * Wrapping(31));
+    Some((plural, x * 2, timeout, hash))
+}
+
+#[derive(Debug, PartialEq)]
+enum Error {
+    Overflow,
+    Other(&'static str),
+}
+
+impl From<&'static str> for Error {
+    fn from(message: &'static str) -> Error {
+        Error::Other(message)
+    }
+}
+
+#[checked(result = Error::Overflow)]
+fn sum(values: &[u8]) -> Result<u8, Error> {
+    let mut total = 0;
+    for value in values {
+        total += *value;
+    }
+    Ok(total)
+}
+
+#[checked(result = "too big")]
+fn square(x: u8) -> Result<u8, Error> {
+    Ok(x * x)
+}
+
+#[checked]
+fn area(width: u8, height: u8) -> u8 {
+    width * height
+}
+
+#[checked(panic)]
+fn increment(x: usize) -> usize {
+    x + 1
+}
+
+struct Counter(u8);
+
+impl Counter {
+    #[checked(option)]
+    fn bump(&mut self, by: u8) -> Option<u8> {
+        self.0 += by;
+        Some(self.0)
+    }
+}
+
+#[test]
+fn binary_operators() {
+    assert_eq!(polynomial(10), Some(287));
+    assert_eq!(polynomial(-10), Some(327));
+    assert_eq!(polynomial(30_000), None);
+    assert_eq!(polynomial(-30_000), None);
+    assert_eq!(shifts(3, 4), Some(24));
+    assert_eq!(shifts(3, 16), None);
+    assert_eq!(divide(7, 2), Some((3, 1)));
+    assert_eq!(divide(7, 0), None);
+}
+
+#[test]
+fn unary_operators() {
+    assert_eq!(negate(5), Some(-6));
+    assert_eq!(negate(-128), None);
+}
+
+#[test]
+fn assignment_operators() {
+    let mut v = [1, 2];
+    assert_eq!(assignments(10, &mut v), Some(19));
+    assert_eq!(v, [1, 21]);
+    assert_eq!(assignments(u32::MAX, &mut v), None);
+    assert_eq!(assignments(u32::MAX / 2, &mut v), None);
+
+    let mut counter = Counter(250);
+    assert_eq!(counter.bump(5), Some(255));
+    assert_eq!(counter.bump(1), None);
+    assert_eq!(counter.0, 255);
+}
+
+#[test]
+fn closures_items_and_floats_are_untouched() {
+    assert_eq!(untouched(2, 1.5), Some((1, 2, 2.0, [0; 4])));
+}
+
+#[test]
+fn constants_in_types_are_untouched() {
+    assert_eq!(constants(1), Some((8, [0; 4])));
+    assert_eq!(constants(usize::MAX), None);
+}
+
+#[test]
+fn other_types_are_left_alone() {
+    let elapsed = Duration::from_millis(1_500);
+    assert_eq!(
+        other_types("frame".to_string(), 100, elapsed),
+        Some(("frames".to_string(), 200, Duration::from_secs(4), Wrapping(28)))
+    );
+    assert_eq!(other_types("frame".to_string(), 200, elapsed), None);
+}
+
+#[test]
+fn result_mode() {
+    assert_eq!(sum(&[1, 2, 3]), Ok(6));
+    assert_eq!(sum(&[200, 56]), Err(Error::Overflow));
+    assert_eq!(square(15), Ok(225));
+    assert_eq!(square(16), Err(Error::Other("too big")));
+}
+
+#[test]
+fn panic_mode() {
+    assert_eq!(area(15, 17), 255);
+    assert_eq!(increment(1), 2);
+}
+
+#[test]
+#[should_panic(expected = "arithmetic overflow in `width * height`")]
+fn panic_mode_overflow() {
+    area(16, 16);
+}
+
+#[test]
+#[should_panic(expected = "arithmetic overflow in `x + 1`")]
+fn panic_mode_location() {
+    increment(usize::MAX);
+}
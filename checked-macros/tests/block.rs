@@ -0,0 +1,99 @@
+#[macro_use]
+extern crate checked;
+
+use checked::Checked;
+use std::hint::black_box;
+
+#[test]
+fn expressions() {
+    let (a, b, c) = (200_u8, 100_u8, 3_u8);
+    assert_eq!(checked!(a), Checked::from(200));
+    assert_eq!(checked!(a + b), Checked::from(None));
+    assert_eq!(checked!(a - b * 2), Checked::from(0));
+    assert_eq!(checked!((a - b) * 2 + c), Checked::from(203));
+    assert_eq!(checked!(a / (c - 3)), Checked::from(None));
+    assert_eq!(checked!(a % c ^ 1 | 4 & 7), Checked::from(7));
+    assert_eq!(checked!(1 << c as u32), Checked::from(8));
+    assert_eq!(checked!(a << 8), Checked::from(None));
+    assert_eq!(checked!(-(a as i16) * 200), Checked::from(None));
+    assert_eq!(checked!(-(a as i32) * 200), Checked::from(-40_000));
+    assert_eq!(checked!(-c), Checked::from(None));
+    assert_eq!(checked!(!c), Checked::from(252));
+    assert_eq!(checked!(a + 56), Checked::from(None));
+    assert_eq!(checked!(a + (50 + 6)), Checked::from(None));
+    assert_eq!(checked!(2 * 3), Checked::from(6));
+}
+
+#[test]
+fn operands() {
+    struct Header {
+        len: u32,
+        counts: (u32, u32),
+    }
+
+    fn entry_size() -> u32 {
+        24
+    }
+
+    let header = Header { len: 16, counts: (3, 1_000_000_000) };
+    let sizes = [1_u32, 2, 4];
+    assert_eq!(checked!(header.len + header.counts.0 * entry_size()), Checked::from(88));
+    assert_eq!(checked!(header.len + header.counts.1 * entry_size()), Checked::from(None));
+    assert_eq!(checked!(sizes[1] * sizes.len() as u32 + u32::MAX / 2), Checked::from(u32::MAX / 2 + 6));
+    assert_eq!(checked!(u32::pow(2, 31) * sizes[1]), Checked::from(None));
+    assert_eq!(checked!(Checked::from(5_u32) * 5), Checked::from(25));
+}
+
+#[test]
+fn blocks() {
+    let (count, size) = (1_000_u64, 1_u64 << 40);
+    let total = checked! {
+        let header = 64;
+        let mut body = count * size;
+        body = body / 2;
+        body += 1;
+        body -= 1;
+        header + body
+    };
+    assert_eq!(total, Checked::from(64 + (1_000 << 39)));
+
+    let total = checked! {
+        let body = count * size * size;
+        (body + 1) * 2
+    };
+    assert_eq!(total, Checked::from(None));
+}
+
+#[test]
+fn literal_bindings() {
+    let x: u8 = black_box(200);
+    let total = checked! {
+        let mut n = 200;
+        n += 100;
+        n + x
+    };
+    assert_eq!(total, Checked::from(None));
+
+    let total = checked! {
+        let mut n = 0;
+        n = 250;
+        n += x / 40;
+        n
+    };
+    assert_eq!(total, Checked::from(255_u8));
+}
+
+#[test]
+fn long_formula() {
+    let a = black_box(600_u16);
+    let total = checked!(
+        a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a +
+        a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a +
+        a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a +
+        a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a + a +
+        a + a + a + a + a + a + a + a + a + a + a + a
+    );
+    assert_eq!(total, Checked::from(60_000));
+    let total = checked!(total + a * 10);
+    assert_eq!(total, Checked::from(None));
+}
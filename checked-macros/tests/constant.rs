@@ -0,0 +1,30 @@
+extern crate checked;
+
+use checked::const_checked;
+
+const PAGE: u32 = 4096;
+const PAGES: u32 = 256;
+
+const fn kib(x: u32) -> u32 {
+    x * 1024
+}
+
+const BUFFER: u32 = const_checked!(u32: PAGE * PAGES + kib(4));
+const SHIFTED: u8 = const_checked!(u8: 1 << (3 + 4));
+const SIGNED: i8 = const_checked!(i8: -(-127 - 1 + 1) % 5 - 126 / 2);
+const WIDE: u64 = const_checked!(u64: u32::MAX as u64 * 2);
+
+#[test]
+fn constants() {
+    assert_eq!(BUFFER, 4096 * 256 + 4096);
+    assert_eq!(SHIFTED, 128);
+    assert_eq!(SIGNED, 2 - 63);
+    assert_eq!(WIDE, 8_589_934_590);
+}
+
+#[test]
+fn in_expressions() {
+    let size = const_checked!(usize: 1 << 16) + 1;
+    assert_eq!(size, 65_537);
+    assert_eq!([0_u8; const_checked!(usize: 2 * 3)].len(), 6);
+}
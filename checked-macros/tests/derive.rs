@@ -0,0 +1,105 @@
+// The reference forms are tested on purpose.
+#![allow(clippy::op_ref)]
+
+extern crate checked;
+
+use checked::{Checked, CheckedOps};
+
+#[derive(CheckedOps, Clone, Copy, Debug, PartialEq)]
+struct Frames(u32);
+
+#[derive(CheckedOps, Clone, Copy, Debug, PartialEq)]
+struct Offset(i16);
+
+#[derive(CheckedOps, Clone, Copy, Debug, PartialEq)]
+#[checked_ops(Add, Sub)]
+#[checked_ops(Div, Rem)]
+struct Bytes(u64);
+
+// A newtype of a newtype goes through the inner one's operators.
+#[derive(CheckedOps, Clone, Copy, Debug, PartialEq)]
+#[checked_ops(Add)]
+struct Total(Frames);
+
+#[test]
+fn binary_operators() {
+    let x = Checked::new(Frames(6));
+    assert_eq!(x + Frames(2), Checked::new(Frames(8)));
+    assert_eq!(x - x, Checked::new(Frames(0)));
+    assert_eq!(x * Frames(7), Checked::new(Frames(42)));
+    assert_eq!(x / Frames(4), Checked::new(Frames(1)));
+    assert_eq!(x % Frames(4), Checked::new(Frames(2)));
+    assert_eq!(x << 2, Checked::new(Frames(24)));
+    assert_eq!(x >> Checked::new(1), Checked::new(Frames(3)));
+
+    assert_eq!(x - Frames(7), Checked::OVERFLOW);
+    assert_eq!(x * Frames(u32::MAX), Checked::OVERFLOW);
+    assert_eq!(x / Frames(0), Checked::OVERFLOW);
+    assert_eq!(x << 32, Checked::OVERFLOW);
+    assert_eq!(Checked::<Frames>::OVERFLOW + x, Checked::OVERFLOW);
+}
+
+#[test]
+fn reverse_and_reference_operators() {
+    let x = Checked::new(Frames(6));
+    assert_eq!(Frames(1) + x, Checked::new(Frames(7)));
+    assert_eq!(Frames(1) - x, Checked::OVERFLOW);
+    assert_eq!(&Frames(2) * &x, Checked::new(Frames(12)));
+    assert_eq!(&x + &Frames(1), Checked::new(Frames(7)));
+    assert_eq!(&x + Frames(1), Checked::new(Frames(7)));
+    assert_eq!(Frames(1) << Checked::new(3), Checked::new(Frames(8)));
+}
+
+#[test]
+fn assignment_operators() {
+    let mut x = Checked::new(Frames(5));
+    x += Frames(1);
+    x *= Checked::new(Frames(3));
+    x -= Frames(2);
+    x <<= 1;
+    assert_eq!(x, Checked::new(Frames(32)));
+    x -= Frames(33);
+    assert_eq!(x, Checked::OVERFLOW);
+    x += Frames(1);
+    assert_eq!(x, Checked::OVERFLOW);
+}
+
+#[test]
+fn negation() {
+    assert_eq!(-Checked::new(Offset(5)), Checked::new(Offset(-5)));
+    assert_eq!(-Checked::new(Offset(i16::MIN)), Checked::OVERFLOW);
+    assert_eq!(-&Checked::new(Offset(1)), Checked::new(Offset(-1)));
+}
+
+#[test]
+fn bitwise_operators() {
+    let mut x = Checked::new(Frames(0b1100));
+    assert_eq!(x & Frames(0b1010), Checked::new(Frames(0b1000)));
+    assert_eq!(x | Checked::new(Frames(0b0011)), Checked::new(Frames(0b1111)));
+    assert_eq!(Frames(0b1010) ^ x, Checked::new(Frames(0b0110)));
+    assert_eq!(&x & &Frames(0b0100), Checked::new(Frames(0b0100)));
+    assert_eq!(!Checked::new(Offset(0)), Checked::new(Offset(-1)));
+    assert_eq!(!&x, Checked::new(Frames(!0b1100)));
+    assert_eq!(Checked::<Frames>::OVERFLOW | Frames(1), Checked::OVERFLOW);
+    assert_eq!(Frames(6) & Frames(3), Frames(2));
+
+    x ^= Frames(0b0101);
+    x &= Checked::new(Frames(0b0111));
+    x |= Frames(0b10000);
+    assert_eq!(x, Checked::new(Frames(0b10001)));
+}
+
+#[test]
+fn chosen_operators() {
+    let size = Checked::new(Bytes(4096));
+    assert_eq!(size + Bytes(4096) - Bytes(1), Checked::new(Bytes(8191)));
+    assert_eq!(size / Bytes(512) % Bytes(3), Checked::new(Bytes(2)));
+    assert_eq!(Bytes(0) - size, Checked::OVERFLOW);
+}
+
+#[test]
+fn nested_newtypes() {
+    let total = Checked::new(Total(Frames(u32::MAX - 1)));
+    assert_eq!(total + Total(Frames(1)), Checked::new(Total(Frames(u32::MAX))));
+    assert_eq!(total + Total(Frames(2)), Checked::OVERFLOW);
+}
@@ -0,0 +1,79 @@
+extern crate checked;
+
+use checked::{checked_expr, ExprError, Op, Reason, Value};
+
+fn entry_size() -> u32 {
+    24
+}
+
+#[test]
+fn success() {
+    let (len, count) = (16_u32, 3_u32);
+    assert_eq!(checked_expr!(len + count * entry_size()), Ok(88));
+    assert_eq!(checked_expr!((len - count) % 5 << 2 >> 1), Ok(6));
+    assert_eq!(checked_expr!(-(5_i8 - 10) * -2), Ok(-10));
+    assert_eq!(checked_expr!(len as u64 * u64::MAX / u64::MAX), Err(ExprError {
+        expr: "len as u64 * u64 :: MAX",
+        op: Op::Mul,
+        lhs: Value::from(16_u64),
+        rhs: Some(Value::from(u64::MAX)),
+        reason: Reason::PositiveOverflow,
+    }));
+}
+
+#[test]
+fn first_failure() {
+    let (len, count) = (16_u32, 1_000_000_000_u32);
+    let error = checked_expr!(len + count * entry_size() - len).unwrap_err();
+    assert_eq!(error.expr, "count * entry_size()");
+    assert_eq!(error.lhs, Value::from(count));
+    assert_eq!(error.rhs, Some(Value::from(24_u32)));
+    assert_eq!(
+        error.to_string(),
+        "positive overflow in `count * entry_size()`: 1000000000 * 24"
+    );
+
+    let error = checked_expr!(len - count + count).unwrap_err();
+    assert_eq!(error.expr, "len - count");
+    assert_eq!(error.reason, Reason::NegativeOverflow);
+}
+
+#[test]
+fn reasons() {
+    let (x, zero, min) = (5_i32, 0_i32, i32::MIN);
+    assert_eq!(checked_expr!(x / zero).unwrap_err().reason, Reason::DivisionByZero);
+    assert_eq!(checked_expr!(x % (x - x)).unwrap_err().reason, Reason::DivisionByZero);
+    assert_eq!(checked_expr!(x << 40).unwrap_err().reason, Reason::ShiftOverflow);
+
+    let error = checked_expr!(1 + -min).unwrap_err();
+    assert_eq!(error.expr, "- min");
+    assert_eq!((error.op, error.rhs), (Op::Neg, None));
+    assert_eq!(error.reason, Reason::PositiveOverflow);
+}
+
+#[test]
+fn nested() {
+    let x = 200_u8;
+    let result = checked_expr!(x + checked_expr!(x * 2).unwrap_or(100));
+    assert_eq!(result.unwrap_err().expr, "x + checked_expr! (x * 2).unwrap_or(100)");
+}
+
+#[test]
+fn under_other_operators() {
+    let (x, y, mask) = (200_u8, 2_u8, 0x0f_u8);
+    let error = checked_expr!((x * y) & mask).unwrap_err();
+    assert_eq!(error.expr, "x * y");
+    assert_eq!(checked_expr!((x / y) & mask | 1 ^ 3), Ok(0x06));
+
+    let values = [1_u32, 2, 3];
+    let i = 1_usize;
+    assert_eq!(checked_expr!(values[i + 1] * 2), Ok(6));
+    assert_eq!(checked_expr!(values[i - 2]).unwrap_err().expr, "i - 2");
+    assert_eq!(checked_expr!(u32::from(x + 55) as u64 + 1), Ok(256));
+    assert_eq!(checked_expr!(u32::from(x + 56)).unwrap_err().expr, "x + 56");
+    assert_eq!(checked_expr!(-(x as i8 - 100) == 0).unwrap_err().expr, "x as i8 - 100");
+    assert_eq!(checked_expr!(if x > 100 { x - 100 } else { 100 - x }), Ok(100));
+
+    // The right-hand side of `&&` is only evaluated (and checked) if it would have been.
+    assert_eq!(checked_expr!(i > 5 && values[i - 2] > 0), Ok(false));
+}
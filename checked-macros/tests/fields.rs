@@ -0,0 +1,148 @@
+// The reference forms are tested on purpose.
+#![allow(clippy::op_ref)]
+
+extern crate checked;
+
+use checked::{Checked, CheckedFields, CheckedOps};
+
+#[derive(CheckedFields, Clone, Copy, Debug, PartialEq)]
+struct Size {
+    w: u32,
+    h: u32,
+}
+
+#[derive(CheckedFields, Clone, Copy, Debug, PartialEq)]
+#[checked_fields(Add, Sub, Neg, Mul, Mul(i16), Rem(i16), Shr(u32))]
+struct Point(i16, i16, i16);
+
+#[derive(CheckedOps, Clone, Copy, Debug, PartialEq)]
+struct Bytes(u64);
+
+// Fields of different types, one of them a newtype.
+#[derive(CheckedFields, Clone, Copy, Debug, PartialEq)]
+struct Region {
+    offset: Bytes,
+    len: u32,
+}
+
+// Not `Debug` or `PartialEq`, which the checked type doesn't need.
+#[derive(CheckedFields, Clone, Copy)]
+#[checked_fields(Add)]
+struct Opaque(u8);
+
+// The generated code doesn't depend on the prelude names where it's expanded.
+mod shadowed {
+    #![allow(dead_code)]
+
+    use checked::CheckedFields;
+
+    pub struct Some;
+    pub struct None;
+    pub trait From {}
+    pub trait Default {}
+    pub trait PartialEq {}
+    pub trait Eq {}
+
+    #[derive(CheckedFields, Clone, Copy, Debug, PartialEq)]
+    #[checked_fields(Add, Neg, Mul(i8))]
+    pub struct Pair(pub i8, pub i8);
+}
+
+#[test]
+fn default_operators() {
+    let size = CheckedSize::new(Size { w: 640, h: 480 });
+    assert_eq!(size + Size { w: 1, h: 2 }, CheckedSize::new(Size { w: 641, h: 482 }));
+    assert_eq!(size - size, CheckedSize::new(Size { w: 0, h: 0 }));
+    assert_eq!(size * 3, CheckedSize::new(Size { w: 1920, h: 1440 }));
+    assert_eq!(size / Checked::new(2), CheckedSize::new(Size { w: 320, h: 240 }));
+    assert_eq!(2 * size, size * 2);
+    assert_eq!(Checked::new(2) * size, size * 2);
+
+    // One field failing is enough.
+    assert_eq!(size - Size { w: 0, h: 481 }, CheckedSize::OVERFLOW);
+    assert_eq!(size * 10_000_000, CheckedSize::OVERFLOW);
+    assert_eq!(size / 0, CheckedSize::OVERFLOW);
+    assert_eq!(size * Checked::from(None), CheckedSize::OVERFLOW);
+    assert_eq!(CheckedSize::OVERFLOW + size, CheckedSize::OVERFLOW);
+}
+
+#[test]
+fn chosen_operators() {
+    let p = CheckedPoint::new(Point(1, -2, 3));
+    assert_eq!(-p, CheckedPoint::new(Point(-1, 2, -3)));
+    assert_eq!(p * Point(2, 3, 4), CheckedPoint::new(Point(2, -6, 12)));
+    assert_eq!(p * -2_i16, CheckedPoint::new(Point(-2, 4, -6)));
+    assert_eq!(p % 2, CheckedPoint::new(Point(1, 0, 1)));
+    assert_eq!(p >> 1, CheckedPoint::new(Point(0, -1, 1)));
+    assert_eq!(-CheckedPoint::new(Point(0, i16::MIN, 0)), CheckedPoint::OVERFLOW);
+    assert_eq!(p >> 16, CheckedPoint::OVERFLOW);
+}
+
+#[test]
+fn reverse_reference_and_assignment_operators() {
+    let p = CheckedPoint::new(Point(1, 2, 3));
+    assert_eq!(Point(1, 1, 1) - p, CheckedPoint::new(Point(0, -1, -2)));
+    assert_eq!(&p + &Point(1, 1, 1), CheckedPoint::new(Point(2, 3, 4)));
+    assert_eq!(&p * 2, p * 2);
+    assert_eq!(-&p, -p);
+
+    let mut q = p;
+    q += Point(1, 1, 1);
+    q *= 2;
+    q -= p;
+    assert_eq!(q, CheckedPoint::new(Point(3, 4, 5)));
+    q *= Checked::new(i16::MAX);
+    assert_eq!(q, CheckedPoint::OVERFLOW);
+    q -= Point(0, 0, 0);
+    assert!(q.is_none());
+}
+
+#[test]
+fn mixed_fields() {
+    let region = CheckedRegion::new(Region {
+        offset: Bytes(4096),
+        len: 512,
+    });
+    let moved = region + Region {
+        offset: Bytes(512),
+        len: 0,
+    };
+    assert_eq!(
+        moved,
+        CheckedRegion::new(Region {
+            offset: Bytes(4608),
+            len: 512,
+        })
+    );
+    let too_far = region - Region {
+        offset: Bytes(8192),
+        len: 0,
+    };
+    assert_eq!(too_far, CheckedRegion::OVERFLOW);
+}
+
+#[test]
+fn conversions() {
+    let size = Size { w: 1, h: 2 };
+    let checked: Checked<Size> = CheckedSize::from(size).into();
+    assert_eq!(checked, Checked::new(size));
+    assert_eq!(CheckedSize::from(checked), CheckedSize::new(size));
+    assert_eq!(CheckedSize::from(None), CheckedSize::default());
+    assert_eq!(*CheckedSize::new(size), Some(size));
+    assert_eq!(format!("{:?}", CheckedSize::new(size) * 2), "Size { w: 2, h: 4 }");
+    assert_eq!(format!("{:?}", CheckedSize::OVERFLOW), "\"overflow\"");
+
+    let opaque = CheckedOpaque::new(Opaque(255)) + Opaque(1);
+    assert!(opaque.is_none());
+}
+
+#[test]
+fn shadowed_prelude() {
+    use shadowed::{CheckedPair, Pair};
+
+    let x = CheckedPair::new(Pair(100, -100));
+    assert_eq!((x + Pair(27, -28)).map(|p| (p.0, p.1)), Some((127, -128)));
+    assert_eq!(x * 2, CheckedPair::OVERFLOW);
+    assert_eq!((-x).map(|p| p.1), Some(100));
+    assert_eq!(CheckedPair::default(), CheckedPair::from(None));
+}
@@ -0,0 +1,275 @@
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::*;
+
+use num::{fmt_overflow, Checked};
+
+/// A checked integer for hot loops, which keeps computing after a failure and remembers that it
+/// happened.
+///
+/// Every operator of `Checked` branches on whether its operands are valid, which stops the
+/// compiler from vectorizing loops over them. `CheckedAcc` instead does the wrapping operation and
+/// ORs its overflow bit into a flag, with no branches at all. Once the flag is set it stays set, and
+/// the value stops meaning anything. Convert to `Checked` at the end of the loop to get the
+/// result.
+///
+/// Division and remainder by zero set the flag rather than panicking, and so do shifts by at least
+/// the number of bits, like the other operators of `Checked`. Failures here don't go to the
+/// [hooks](hook/index.html), since finding them would take the branch this is meant to avoid.
+/// # Examples
+/// ```
+/// use checked::{Checked, CheckedAcc};
+///
+/// let samples = [40_000_u32; 1_000];
+/// let mut sum = CheckedAcc::new(0_u32);
+/// for &x in samples.iter() {
+///     sum += x;
+/// }
+/// assert_eq!(Checked::from(sum), Checked::new(40_000_000));
+///
+/// let mut product = CheckedAcc::new(1_u32);
+/// for &x in samples.iter() {
+///     product *= x;
+/// }
+/// assert_eq!(product.to_checked(), Checked::OVERFLOW);
+/// ```
+#[derive(Clone, Copy)]
+pub struct CheckedAcc<T> {
+    value: T,
+    overflow: bool,
+}
+
+impl<T> CheckedAcc<T> {
+    /// Creates a new CheckedAcc instance, with the flag clear.
+    #[inline]
+    pub const fn new(x: T) -> CheckedAcc<T> {
+        CheckedAcc {
+            value: x,
+            overflow: false,
+        }
+    }
+
+    /// Whether any operation has failed.
+    #[inline]
+    pub fn overflowed(&self) -> bool {
+        self.overflow
+    }
+
+    /// The value, or `None` if any operation failed.
+    #[inline]
+    pub fn to_checked(self) -> Checked<T> {
+        if self.overflow {
+            Checked(None)
+        } else {
+            Checked(Some(self.value))
+        }
+    }
+
+    /// The value and the flag. After a failure, the value is what wrapping arithmetic gives.
+    #[inline]
+    pub fn into_parts(self) -> (T, bool) {
+        (self.value, self.overflow)
+    }
+}
+
+impl<T: Default> Default for CheckedAcc<T> {
+    #[inline]
+    fn default() -> CheckedAcc<T> {
+        CheckedAcc::new(T::default())
+    }
+}
+
+impl<T> From<T> for CheckedAcc<T> {
+    #[inline]
+    fn from(x: T) -> CheckedAcc<T> {
+        CheckedAcc::new(x)
+    }
+}
+
+impl<T: Default> From<Checked<T>> for CheckedAcc<T> {
+    #[inline]
+    fn from(x: Checked<T>) -> CheckedAcc<T> {
+        match x.0 {
+            Some(x) => CheckedAcc::new(x),
+            None => CheckedAcc {
+                value: T::default(),
+                overflow: true,
+            },
+        }
+    }
+}
+
+impl<T> From<CheckedAcc<T>> for Checked<T> {
+    #[inline]
+    fn from(x: CheckedAcc<T>) -> Checked<T> {
+        x.to_checked()
+    }
+}
+
+// Compares as `Checked` would: all failed values are equal, whatever their wrapped values.
+impl<T: PartialEq> PartialEq for CheckedAcc<T> {
+    #[inline]
+    fn eq(&self, other: &CheckedAcc<T>) -> bool {
+        match (self.overflow, other.overflow) {
+            (false, false) => self.value == other.value,
+            (a, b) => a == b,
+        }
+    }
+}
+
+impl<T: Eq> Eq for CheckedAcc<T> {}
+
+impl<T: Hash> Hash for CheckedAcc<T> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.overflow.hash(state);
+        if !self.overflow {
+            self.value.hash(state);
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for CheckedAcc<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.overflow {
+            fmt_overflow(f, true)
+        } else {
+            self.value.fmt(f)
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for CheckedAcc<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.overflow {
+            fmt_overflow(f, false)
+        } else {
+            self.value.fmt(f)
+        }
+    }
+}
+
+// implements binary operators for accumulators, with `$f(x, y)` giving the result and overflow bit
+// (the flags are combined with `|`, which clippy finds suspicious in arithmetic)
+macro_rules! impl_acc_binop {
+    (impl $imp:ident, $method:ident, $imp_assign:ident, $method_assign:ident for $t:ident, $u:ty: |$x:ident, $y:ident| $f:expr) => {
+        impl $imp<$u> for CheckedAcc<$t> {
+            type Output = CheckedAcc<$t>;
+
+            #[inline]
+            #[allow(clippy::suspicious_arithmetic_impl)]
+            fn $method(self, other: $u) -> CheckedAcc<$t> {
+                let ($x, $y) = (self.value, other);
+                let (value, overflow) = $f;
+                CheckedAcc {
+                    value,
+                    overflow: self.overflow | overflow,
+                }
+            }
+        }
+
+        impl $imp_assign<$u> for CheckedAcc<$t> {
+            #[inline]
+            fn $method_assign(&mut self, other: $u) {
+                *self = $imp::$method(*self, other);
+            }
+        }
+
+        forward_ref_binop! { impl $imp, $method for CheckedAcc<$t>, $u {} }
+    };
+    (impl $imp:ident, $method:ident, $imp_assign:ident, $method_assign:ident for $t:ident: |$x:ident, $y:ident| $f:expr) => {
+        impl_acc_binop! { impl $imp, $method, $imp_assign, $method_assign for $t, $t: |$x, $y| $f }
+
+        impl $imp for CheckedAcc<$t> {
+            type Output = CheckedAcc<$t>;
+
+            #[inline]
+            #[allow(clippy::suspicious_arithmetic_impl)]
+            fn $method(self, other: CheckedAcc<$t>) -> CheckedAcc<$t> {
+                let result = $imp::$method(self, other.value);
+                CheckedAcc {
+                    value: result.value,
+                    overflow: result.overflow | other.overflow,
+                }
+            }
+        }
+
+        impl $imp_assign for CheckedAcc<$t> {
+            #[inline]
+            fn $method_assign(&mut self, other: CheckedAcc<$t>) {
+                *self = $imp::$method(*self, other);
+            }
+        }
+
+        impl $imp<CheckedAcc<$t>> for $t {
+            type Output = CheckedAcc<$t>;
+
+            #[inline]
+            fn $method(self, other: CheckedAcc<$t>) -> CheckedAcc<$t> {
+                $imp::$method(CheckedAcc::new(self), other)
+            }
+        }
+
+        forward_ref_binop! { impl $imp, $method for CheckedAcc<$t>, CheckedAcc<$t> {} }
+        forward_ref_binop! { impl $imp, $method for $t, CheckedAcc<$t> {} }
+    };
+}
+
+macro_rules! acc_impl {
+    ($($t:ident)*) => {
+        $(
+            impl_acc_binop! { impl Add, add, AddAssign, add_assign for $t: |x, y| x.overflowing_add(y) }
+            impl_acc_binop! { impl Sub, sub, SubAssign, sub_assign for $t: |x, y| x.overflowing_sub(y) }
+            impl_acc_binop! { impl Mul, mul, MulAssign, mul_assign for $t: |x, y| x.overflowing_mul(y) }
+            // Dividing by 1 instead of 0 keeps this free of branches (and panics).
+            impl_acc_binop! { impl Div, div, DivAssign, div_assign for $t: |x, y| {
+                let zero = y == 0;
+                let (q, overflow) = x.overflowing_div(y | zero as $t);
+                (q, overflow | zero)
+            } }
+            impl_acc_binop! { impl Rem, rem, RemAssign, rem_assign for $t: |x, y| {
+                let zero = y == 0;
+                let (r, overflow) = x.overflowing_rem(y | zero as $t);
+                (r, overflow | zero)
+            } }
+            impl_acc_binop! { impl BitAnd, bitand, BitAndAssign, bitand_assign for $t: |x, y| (x & y, false) }
+            impl_acc_binop! { impl BitOr, bitor, BitOrAssign, bitor_assign for $t: |x, y| (x | y, false) }
+            impl_acc_binop! { impl BitXor, bitxor, BitXorAssign, bitxor_assign for $t: |x, y| (x ^ y, false) }
+            impl_acc_binop! { impl Shl, shl, ShlAssign, shl_assign for $t, u32: |x, y| x.overflowing_shl(y) }
+            impl_acc_binop! { impl Shr, shr, ShrAssign, shr_assign for $t, u32: |x, y| x.overflowing_shr(y) }
+
+            impl Neg for CheckedAcc<$t> {
+                type Output = CheckedAcc<$t>;
+
+                #[inline]
+                fn neg(self) -> CheckedAcc<$t> {
+                    let (value, overflow) = self.value.overflowing_neg();
+                    CheckedAcc {
+                        value,
+                        overflow: self.overflow | overflow,
+                    }
+                }
+            }
+
+            forward_ref_unop! { impl Neg, neg for CheckedAcc<$t> {} }
+
+            impl Not for CheckedAcc<$t> {
+                type Output = CheckedAcc<$t>;
+
+                #[inline]
+                fn not(self) -> CheckedAcc<$t> {
+                    CheckedAcc {
+                        value: !self.value,
+                        overflow: self.overflow,
+                    }
+                }
+            }
+
+            forward_ref_unop! { impl Not, not for CheckedAcc<$t> {} }
+        )*
+    };
+}
+
+acc_impl! { u8 u16 u32 u64 usize i8 i16 i32 i64 isize }
@@ -0,0 +1,288 @@
+//! Sums that add in a wider type, and only check the result when it is narrowed at the end.
+//!
+//! Adding up a million `u32`s in `Checked<u32>` checks every addition, but a `u64` sum of `u32`s
+//! can't overflow until there are more than 2<sup>32</sup> of them. An
+//! [`Accumulator<T>`](struct.Accumulator.html) adds in `T`'s [wide type](trait.Widen.html),
+//! counts the values, and checks only the count as it goes. The sum is checked once, when it is
+//! narrowed back into a `Checked<T>`.
+//! # Examples
+//! ```
+//! use checked::{Accumulator, Checked};
+//!
+//! let sizes = vec![3_000_000_000_u32, 2_000_000_000, 4_000_000_000];
+//! let total: Accumulator<u32> = sizes.iter().cloned().collect();
+//! assert_eq!(total.wide(), Checked::new(9_000_000_000));
+//! assert_eq!(total.to_checked(), Checked::OVERFLOW);
+//!
+//! // The sum only needs to fit at the end.
+//! let deltas = [i8::MAX, i8::MAX, -100, -100];
+//! let net: Accumulator<i8> = deltas.iter().cloned().sum();
+//! assert_eq!(net.to_checked(), Checked::new(54));
+//! ```
+
+use core::convert::TryFrom;
+use core::fmt;
+use core::iter::{FromIterator, Sum};
+use core::ops::{Add, AddAssign};
+
+use num_traits::{WrappingAdd, Zero};
+
+use num::Checked;
+
+/// An integer type with a wider type to sum it in.
+pub trait Widen: Copy {
+    /// The type the sums are done in.
+    type Wide: Copy + WrappingAdd + Zero;
+
+    /// The most values whose sum is sure to fit in `Wide`.
+    const LIMIT: u64;
+
+    /// Converts to the wide type.
+    fn widen(self) -> Self::Wide;
+
+    /// Converts back from the wide type, or `None` if the value doesn't fit.
+    fn narrow(wide: Self::Wide) -> Option<Self>;
+}
+
+// The most values of `bits` bits that always sum to a value of `wide_bits` bits. The largest
+// limits are cut to one below `u64::MAX`, since the count saturates there.
+const fn limit(bits: u32, wide_bits: u32) -> u64 {
+    if wide_bits - bits >= 64 {
+        u64::MAX - 1
+    } else {
+        1 << (wide_bits - bits)
+    }
+}
+
+macro_rules! impl_widen {
+    ($($t:ident => $w:ident,)*) => {
+        $(
+            impl Widen for $t {
+                type Wide = $w;
+
+                const LIMIT: u64 = limit($t::BITS, $w::BITS);
+
+                #[inline]
+                fn widen(self) -> $w {
+                    self as $w
+                }
+
+                #[inline]
+                fn narrow(wide: $w) -> Option<$t> {
+                    $t::try_from(wide).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_widen! {
+    u8 => u64, u16 => u64, u32 => u64, u64 => u128, usize => u128,
+    i8 => i64, i16 => i64, i32 => i64, i64 => i128, isize => i128,
+}
+
+/// A sum of `T`s, added in `T::Wide` without checking each addition.
+///
+/// The additions wrap, which is why the accumulator counts its values: while there are at most
+/// [`T::LIMIT`](trait.Widen.html#associatedconstant.LIMIT) of them, the wide sum can't have
+/// overflowed. Past that, or after adding a failed `Checked<T>`, the sum counts as failed. Two
+/// accumulators can be added together, for sums done in parallel.
+///
+/// None of this goes through the operators of `Checked`, so the [hooks](../hook/index.html) aren't
+/// called when the sum fails.
+/// # Examples
+/// ```
+/// use checked::{Accumulator, Checked};
+///
+/// let mut sum = Accumulator::new();
+/// for _ in 0..100_000 {
+///     sum += 50_000_u16;
+/// }
+/// assert_eq!(sum.count(), 100_000);
+/// assert_eq!(sum.wide(), Checked::new(5_000_000_000));
+/// assert_eq!(Checked::<u16>::from(sum), Checked::OVERFLOW);
+/// ```
+#[derive(Clone, Copy)]
+pub struct Accumulator<T: Widen> {
+    sum: T::Wide,
+    count: u64,
+    failed: bool,
+}
+
+impl<T: Widen> Accumulator<T> {
+    /// Creates an empty accumulator, with a sum of zero.
+    #[inline]
+    pub fn new() -> Accumulator<T> {
+        Accumulator {
+            sum: T::Wide::zero(),
+            count: 0,
+            failed: false,
+        }
+    }
+
+    /// Adds a value.
+    #[inline]
+    pub fn push(&mut self, x: T) {
+        self.sum = self.sum.wrapping_add(&x.widen());
+        self.count = self.count.saturating_add(1);
+    }
+
+    /// How many values have been added, counting failed ones, which stops at `u64::MAX`.
+    #[inline]
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// The sum in the wide type, or `None` if it could have overflowed.
+    #[inline]
+    pub fn wide(&self) -> Checked<T::Wide> {
+        if !self.failed && self.count <= T::LIMIT {
+            Checked(Some(self.sum))
+        } else {
+            Checked(None)
+        }
+    }
+
+    /// The sum, or `None` if it could have overflowed the wide type or doesn't fit in `T`.
+    #[inline]
+    pub fn to_checked(&self) -> Checked<T> {
+        Checked(self.wide().0.and_then(T::narrow))
+    }
+
+    // Adds a failed value, so the sum is failed from here on.
+    #[inline]
+    fn fail(&mut self) {
+        self.count = self.count.saturating_add(1);
+        self.failed = true;
+    }
+}
+
+impl<T: Widen> Default for Accumulator<T> {
+    #[inline]
+    fn default() -> Accumulator<T> {
+        Accumulator::new()
+    }
+}
+
+impl<T: Widen> fmt::Debug for Accumulator<T>
+where
+    T::Wide: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Accumulator")
+            .field("sum", &self.wide())
+            .field("count", &self.count)
+            .finish()
+    }
+}
+
+impl<T: Widen> PartialEq for Accumulator<T>
+where
+    T::Wide: PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &Accumulator<T>) -> bool {
+        self.count == other.count && self.failed == other.failed && self.wide() == other.wide()
+    }
+}
+
+impl<T: Widen> From<Accumulator<T>> for Checked<T> {
+    #[inline]
+    fn from(x: Accumulator<T>) -> Checked<T> {
+        x.to_checked()
+    }
+}
+
+impl<T: Widen> AddAssign<T> for Accumulator<T> {
+    #[inline]
+    fn add_assign(&mut self, x: T) {
+        self.push(x);
+    }
+}
+
+impl<T: Widen> AddAssign<Checked<T>> for Accumulator<T> {
+    #[inline]
+    fn add_assign(&mut self, x: Checked<T>) {
+        match x.0 {
+            Some(x) => self.push(x),
+            None => self.fail(),
+        }
+    }
+}
+
+impl<T: Widen> Add for Accumulator<T> {
+    type Output = Accumulator<T>;
+
+    #[inline]
+    fn add(self, other: Accumulator<T>) -> Accumulator<T> {
+        Accumulator {
+            sum: self.sum.wrapping_add(&other.sum),
+            count: self.count.saturating_add(other.count),
+            failed: self.failed || other.failed,
+        }
+    }
+}
+
+impl<T: Widen> AddAssign for Accumulator<T> {
+    #[inline]
+    fn add_assign(&mut self, other: Accumulator<T>) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Widen> Extend<T> for Accumulator<T> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+impl<T: Widen> Extend<Checked<T>> for Accumulator<T> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = Checked<T>>>(&mut self, iter: I) {
+        for x in iter {
+            *self += x;
+        }
+    }
+}
+
+impl<T: Widen> FromIterator<T> for Accumulator<T> {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Accumulator<T> {
+        let mut sum = Accumulator::new();
+        sum.extend(iter);
+        sum
+    }
+}
+
+impl<T: Widen> FromIterator<Checked<T>> for Accumulator<T> {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = Checked<T>>>(iter: I) -> Accumulator<T> {
+        let mut sum = Accumulator::new();
+        sum.extend(iter);
+        sum
+    }
+}
+
+impl<T: Widen> Sum<T> for Accumulator<T> {
+    #[inline]
+    fn sum<I: Iterator<Item = T>>(iter: I) -> Accumulator<T> {
+        iter.collect()
+    }
+}
+
+impl<T: Widen> Sum<Checked<T>> for Accumulator<T> {
+    #[inline]
+    fn sum<I: Iterator<Item = Checked<T>>>(iter: I) -> Accumulator<T> {
+        iter.collect()
+    }
+}
+
+impl<T: Widen> Sum for Accumulator<T> {
+    #[inline]
+    fn sum<I: Iterator<Item = Accumulator<T>>>(iter: I) -> Accumulator<T> {
+        iter.fold(Accumulator::new(), Add::add)
+    }
+}
@@ -0,0 +1,278 @@
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::num::Wrapping;
+use core::ops::*;
+
+use num_traits::{One, Zero};
+
+use failure::Op;
+use num::{fmt_overflow, Checked};
+use policy::{self, Policy};
+use strict::Strict;
+
+/// An integer whose failed operations are handled by the policy `P`.
+///
+/// An operation that succeeds gives the same result whatever the policy. When one fails, the
+/// [policy](policy/index.html) decides what happens: `policy::Checked` goes into the failed
+/// state like `Checked<T>` does, `policy::Saturating` and `policy::Wrapping` give the saturated or
+/// wrapped result, and `policy::Strict` panics like `Strict<T>`. Once in the failed state, every
+/// operation stays there.
+///
+/// As with `Checked<T>`, failures are reported to the [hooks](hook/index.html) before the policy
+/// sees them, whatever the policy, and with the `stats` feature the operations are counted in the
+/// [statistics](stats/index.html). The operators of `Checked<T>` are those of
+/// `Arith<T, policy::Checked>`, so the two behave exactly alike, and they convert into each other, as do the other built-in policies and `Strict<T>` or
+/// `std::num::Wrapping<T>`.
+/// [`with_policy`](#method.with_policy) switches between policies.
+/// # Examples
+/// ```
+/// use checked::Arith;
+/// use checked::policy::{Checked, Saturating, Wrapping};
+///
+/// let x = Arith::<u8, Checked>::new(200);
+/// assert_eq!(x + 100, Arith::OVERFLOW);
+///
+/// let x = x.with_policy::<Saturating>();
+/// assert_eq!(x + 100, Arith::new(255));
+/// assert_eq!(x - 250, Arith::new(0));
+///
+/// let x = x.with_policy::<Wrapping>();
+/// assert_eq!(x + 100, Arith::new(44));
+/// ```
+pub struct Arith<T, P> {
+    value: Option<T>,
+    policy: PhantomData<P>,
+}
+
+impl<T, P> Arith<T, P> {
+    /// Creates a new Arith instance.
+    /// # Examples
+    /// ```
+    /// use checked::Arith;
+    /// use checked::policy::Saturating;
+    ///
+    /// let x = Arith::<i8, Saturating>::new(-100);
+    /// assert_eq!(x * 2, Arith::new(-128));
+    /// ```
+    #[inline]
+    pub const fn new(x: T) -> Arith<T, P> {
+        Arith {
+            value: Some(x),
+            policy: PhantomData,
+        }
+    }
+
+    /// The failed state.
+    pub const OVERFLOW: Arith<T, P> = Arith {
+        value: None,
+        policy: PhantomData,
+    };
+
+    /// The value, or `None` in the failed state.
+    #[inline]
+    pub fn get(self) -> Option<T> {
+        self.value
+    }
+
+    /// The same value, with a different policy for the operations from now on.
+    /// # Examples
+    /// ```
+    /// use checked::Arith;
+    /// use checked::policy::{Checked, Wrapping};
+    ///
+    /// let hash = Arith::<u32, Wrapping>::new(0x811c_9dc5) * 0x0100_0193;
+    /// assert_eq!(hash.with_policy::<Checked>() + 1, Arith::new(0x050c_5d20));
+    /// ```
+    #[inline]
+    pub fn with_policy<Q>(self) -> Arith<T, Q> {
+        Arith {
+            value: self.value,
+            policy: PhantomData,
+        }
+    }
+}
+
+// These are written out because deriving them would require `P` to implement them too.
+impl<T: Clone, P> Clone for Arith<T, P> {
+    #[inline]
+    fn clone(&self) -> Arith<T, P> {
+        Arith {
+            value: self.value.clone(),
+            policy: PhantomData,
+        }
+    }
+}
+
+impl<T: Copy, P> Copy for Arith<T, P> {}
+
+impl<T, P> Default for Arith<T, P> {
+    #[inline]
+    fn default() -> Arith<T, P> {
+        Arith::OVERFLOW
+    }
+}
+
+impl<T: PartialEq, P> PartialEq for Arith<T, P> {
+    #[inline]
+    fn eq(&self, other: &Arith<T, P>) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Eq, P> Eq for Arith<T, P> {}
+
+impl<T: PartialOrd, P> PartialOrd for Arith<T, P> {
+    fn partial_cmp(&self, other: &Arith<T, P>) -> Option<Ordering> {
+        // As for `Checked`, the failed state can't be compared.
+        match (&self.value, &other.value) {
+            (Some(x), Some(y)) => PartialOrd::partial_cmp(x, y),
+            _ => None,
+        }
+    }
+}
+
+impl<T: Hash, P> Hash for Arith<T, P> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state)
+    }
+}
+
+impl<T: fmt::Debug, P> fmt::Debug for Arith<T, P> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.value {
+            Some(ref x) => x.fmt(f),
+            None => fmt_overflow(f, true),
+        }
+    }
+}
+
+impl<T: fmt::Display, P> fmt::Display for Arith<T, P> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.value {
+            Some(ref x) => x.fmt(f),
+            None => fmt_overflow(f, false),
+        }
+    }
+}
+
+// implements the radix and exponent formatting traits
+macro_rules! impl_arith_fmt {
+    ($($imp:ident)*) => {
+        $(
+            impl<T: fmt::$imp, P> fmt::$imp for Arith<T, P> {
+                #[inline]
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    match self.value {
+                        Some(ref x) => x.fmt(f),
+                        None => fmt_overflow(f, false),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_arith_fmt! { LowerHex UpperHex Octal Binary LowerExp UpperExp }
+
+impl<T, P> Deref for Arith<T, P> {
+    type Target = Option<T>;
+
+    #[inline]
+    fn deref(&self) -> &Option<T> {
+        &self.value
+    }
+}
+
+impl<T, P> From<T> for Arith<T, P> {
+    #[inline]
+    fn from(x: T) -> Arith<T, P> {
+        Arith::new(x)
+    }
+}
+
+impl<T, P> From<Option<T>> for Arith<T, P> {
+    #[inline]
+    fn from(x: Option<T>) -> Arith<T, P> {
+        Arith {
+            value: x,
+            policy: PhantomData,
+        }
+    }
+}
+
+impl<T> From<Checked<T>> for Arith<T, policy::Checked> {
+    #[inline]
+    fn from(x: Checked<T>) -> Arith<T, policy::Checked> {
+        Arith {
+            value: x.0,
+            policy: PhantomData,
+        }
+    }
+}
+
+impl<T> From<Arith<T, policy::Checked>> for Checked<T> {
+    #[inline]
+    fn from(x: Arith<T, policy::Checked>) -> Checked<T> {
+        Checked(x.value)
+    }
+}
+
+impl<T> From<Wrapping<T>> for Arith<T, policy::Wrapping> {
+    #[inline]
+    fn from(x: Wrapping<T>) -> Arith<T, policy::Wrapping> {
+        Arith::new(x.0)
+    }
+}
+
+impl<T> From<Strict<T>> for Arith<T, policy::Strict> {
+    #[inline]
+    fn from(x: Strict<T>) -> Arith<T, policy::Strict> {
+        Arith::new(x.0)
+    }
+}
+
+impl<T, P> Zero for Arith<T, P>
+where
+    T: Zero,
+    Arith<T, P>: Add<Arith<T, P>, Output = Arith<T, P>>,
+{
+    fn zero() -> Arith<T, P> {
+        Arith::new(T::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.value.as_ref().is_some_and(Zero::is_zero)
+    }
+}
+
+impl<T, P> One for Arith<T, P>
+where
+    T: One,
+    Arith<T, P>: Mul<Arith<T, P>, Output = Arith<T, P>>,
+{
+    fn one() -> Arith<T, P> {
+        Arith::new(T::one())
+    }
+}
+
+macro_rules! arith_impl {
+    ($($t:ident)*) => {
+        $(
+            impl_ops! { impl [P: Policy] for Arith<$t, P>, $t, P }
+            impl_sh! { impl [P: Policy] $t, u32 for Arith<$t, P>, Arith<u32, P>, P }
+            impl_sh_reverse! { impl [P: Policy] u32, $t for Arith<u32, P>, Arith<$t, P>, P }
+        )*
+    };
+}
+
+arith_impl! { u8 u16 u32 u64 usize i8 i16 i32 i64 isize }
+
+impl_bitop! { impl [T: Copy, P] BitAnd, bitand, BitAndAssign, bitand_assign for Arith<T, P>, T {&} }
+impl_bitop! { impl [T: Copy, P] BitOr, bitor, BitOrAssign, bitor_assign for Arith<T, P>, T {|} }
+impl_bitop! { impl [T: Copy, P] BitXor, bitxor, BitXorAssign, bitxor_assign for Arith<T, P>, T {^} }
+impl_bitop! { impl [T: Copy, P] Not, not for Arith<T, P>, T }
@@ -0,0 +1,362 @@
+use core::ops::*;
+
+use num_traits::{One, Zero};
+
+use num::Checked;
+
+/// A fixed-size array of checked values, with element-wise operators.
+///
+/// Each operator works out every element with the operator of `Checked`, so an element fails on
+/// its own without affecting the others. The right-hand side can be another array, or a single
+/// `T` or `Checked<T>` that is applied to every element. [`sum`](#method.sum),
+/// [`product`](#method.product), [`min`](#method.min) and [`max`](#method.max) combine the
+/// elements into one value, and [`all_valid`](#method.all_valid) tells whether any have failed.
+/// # Examples
+/// ```
+/// use checked::{Checked, CheckedArray};
+///
+/// let a = CheckedArray::from([1_i32, 2, 3, i32::MAX]);
+/// let b = CheckedArray::from([10, 20, 30, 40]);
+/// let c = a * 2 + b;
+/// assert_eq!(c[0], Checked::new(12));
+/// assert_eq!(c[3], Checked::OVERFLOW);
+/// assert!(!c.all_valid());
+///
+/// let d = (a - 1) / CheckedArray::from([1, 1, 2, 1]);
+/// assert_eq!(d.get(), Some([0, 1, 1, i32::MAX - 1]));
+/// assert_eq!(d.sum(), Checked::OVERFLOW);
+/// assert_eq!(d.max(), Checked::new(i32::MAX - 1));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CheckedArray<T, const N: usize>(pub [Checked<T>; N]);
+
+impl<T: Copy, const N: usize> CheckedArray<T, N> {
+    /// Creates a new CheckedArray instance, with all the values valid.
+    #[inline]
+    pub fn new(values: [T; N]) -> CheckedArray<T, N> {
+        CheckedArray(values.map(Checked::new))
+    }
+
+    /// Whether none of the elements have failed.
+    #[inline]
+    pub fn all_valid(&self) -> bool {
+        self.0.iter().all(|x| x.is_some())
+    }
+
+    /// The values, or `None` if any of the elements have failed.
+    pub fn get(&self) -> Option<[T; N]> {
+        if self.all_valid() {
+            Some(self.0.map(|x| x.0.unwrap()))
+        } else {
+            None
+        }
+    }
+
+    /// The sum of the elements, as added up by `Checked`, or `None` if any of them failed.
+    #[track_caller]
+    pub fn sum(&self) -> Checked<T>
+    where
+        T: Zero,
+        Checked<T>: Add<Output = Checked<T>>,
+    {
+        let mut sum = Checked::zero();
+        for &x in self.0.iter() {
+            sum = sum + x;
+        }
+        sum
+    }
+
+    /// The product of the elements, as multiplied by `Checked`, or `None` if any of them failed.
+    #[track_caller]
+    pub fn product(&self) -> Checked<T>
+    where
+        T: One,
+        Checked<T>: Mul<Output = Checked<T>>,
+    {
+        let mut product = Checked::one();
+        for &x in self.0.iter() {
+            product = product * x;
+        }
+        product
+    }
+
+    /// The smallest element, or `None` if any of them failed or the array is empty.
+    pub fn min(&self) -> Checked<T>
+    where
+        T: Ord,
+    {
+        Checked(self.get().and_then(|values| values.iter().cloned().min()))
+    }
+
+    /// The largest element, or `None` if any of them failed or the array is empty.
+    pub fn max(&self) -> Checked<T>
+    where
+        T: Ord,
+    {
+        Checked(self.get().and_then(|values| values.iter().cloned().max()))
+    }
+}
+
+impl<T, const N: usize> Deref for CheckedArray<T, N> {
+    type Target = [Checked<T>; N];
+
+    #[inline]
+    fn deref(&self) -> &[Checked<T>; N] {
+        &self.0
+    }
+}
+
+impl<T, const N: usize> DerefMut for CheckedArray<T, N> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [Checked<T>; N] {
+        &mut self.0
+    }
+}
+
+impl<T: Copy, const N: usize> From<[T; N]> for CheckedArray<T, N> {
+    #[inline]
+    fn from(values: [T; N]) -> CheckedArray<T, N> {
+        CheckedArray::new(values)
+    }
+}
+
+impl<T, const N: usize> From<[Checked<T>; N]> for CheckedArray<T, N> {
+    #[inline]
+    fn from(values: [Checked<T>; N]) -> CheckedArray<T, N> {
+        CheckedArray(values)
+    }
+}
+
+impl<T, const N: usize> From<CheckedArray<T, N>> for [Checked<T>; N] {
+    #[inline]
+    fn from(x: CheckedArray<T, N>) -> [Checked<T>; N] {
+        x.0
+    }
+}
+
+// implements element-wise binary operators by those of `Checked`, with the forms that broadcast
+// a single value and the forms with references
+macro_rules! impl_array_binop {
+    ($($imp:ident, $method:ident, $imp_assign:ident, $method_assign:ident;)*) => {
+        $(
+            impl<T: Copy, const N: usize> $imp for CheckedArray<T, N>
+            where
+                Checked<T>: $imp<Output = Checked<T>>,
+            {
+                type Output = CheckedArray<T, N>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: CheckedArray<T, N>) -> CheckedArray<T, N> {
+                    let mut result = self;
+                    for (x, &y) in result.0.iter_mut().zip(other.0.iter()) {
+                        *x = $imp::$method(*x, y);
+                    }
+                    result
+                }
+            }
+
+            impl<T: Copy, const N: usize> $imp<Checked<T>> for CheckedArray<T, N>
+            where
+                Checked<T>: $imp<Output = Checked<T>>,
+            {
+                type Output = CheckedArray<T, N>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: Checked<T>) -> CheckedArray<T, N> {
+                    let mut result = self;
+                    for x in result.0.iter_mut() {
+                        *x = $imp::$method(*x, other);
+                    }
+                    result
+                }
+            }
+
+            impl<T: Copy, const N: usize> $imp<T> for CheckedArray<T, N>
+            where
+                Checked<T>: $imp<Output = Checked<T>>,
+            {
+                type Output = CheckedArray<T, N>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: T) -> CheckedArray<T, N> {
+                    $imp::$method(self, Checked::new(other))
+                }
+            }
+
+            impl<'a, T: Copy, const N: usize> $imp<&'a CheckedArray<T, N>> for CheckedArray<T, N>
+            where
+                Checked<T>: $imp<Output = Checked<T>>,
+            {
+                type Output = CheckedArray<T, N>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: &'a CheckedArray<T, N>) -> CheckedArray<T, N> {
+                    $imp::$method(self, *other)
+                }
+            }
+
+            impl<'a, T: Copy, U, const N: usize> $imp<U> for &'a CheckedArray<T, N>
+            where
+                CheckedArray<T, N>: $imp<U, Output = CheckedArray<T, N>>,
+            {
+                type Output = CheckedArray<T, N>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: U) -> CheckedArray<T, N> {
+                    $imp::$method(*self, other)
+                }
+            }
+
+            impl<T: Copy, U, const N: usize> $imp_assign<U> for CheckedArray<T, N>
+            where
+                CheckedArray<T, N>: $imp<U, Output = CheckedArray<T, N>>,
+            {
+                #[inline]
+                #[track_caller]
+                fn $method_assign(&mut self, other: U) {
+                    *self = $imp::$method(*self, other);
+                }
+            }
+        )*
+    };
+}
+
+impl_array_binop! {
+    Add, add, AddAssign, add_assign;
+    Sub, sub, SubAssign, sub_assign;
+    Mul, mul, MulAssign, mul_assign;
+    Div, div, DivAssign, div_assign;
+    Rem, rem, RemAssign, rem_assign;
+    BitAnd, bitand, BitAndAssign, bitand_assign;
+    BitOr, bitor, BitOrAssign, bitor_assign;
+    BitXor, bitxor, BitXorAssign, bitxor_assign;
+}
+
+// implements shifts of every element by the same `u32`, as for `Checked`
+macro_rules! impl_array_sh {
+    ($($imp:ident, $method:ident, $imp_assign:ident, $method_assign:ident;)*) => {
+        $(
+            impl<T: Copy, const N: usize> $imp<u32> for CheckedArray<T, N>
+            where
+                Checked<T>: $imp<u32, Output = Checked<T>>,
+            {
+                type Output = CheckedArray<T, N>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: u32) -> CheckedArray<T, N> {
+                    let mut result = self;
+                    for x in result.0.iter_mut() {
+                        *x = $imp::$method(*x, other);
+                    }
+                    result
+                }
+            }
+
+            impl<'a, T: Copy, const N: usize> $imp<u32> for &'a CheckedArray<T, N>
+            where
+                Checked<T>: $imp<u32, Output = Checked<T>>,
+            {
+                type Output = CheckedArray<T, N>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: u32) -> CheckedArray<T, N> {
+                    $imp::$method(*self, other)
+                }
+            }
+
+            impl<T: Copy, const N: usize> $imp_assign<u32> for CheckedArray<T, N>
+            where
+                Checked<T>: $imp<u32, Output = Checked<T>>,
+            {
+                #[inline]
+                #[track_caller]
+                fn $method_assign(&mut self, other: u32) {
+                    *self = $imp::$method(*self, other);
+                }
+            }
+        )*
+    };
+}
+
+impl_array_sh! {
+    Shl, shl, ShlAssign, shl_assign;
+    Shr, shr, ShrAssign, shr_assign;
+}
+
+// implements element-wise unary operators by those of `Checked`
+macro_rules! impl_array_unop {
+    ($($imp:ident, $method:ident;)*) => {
+        $(
+            impl<T: Copy, const N: usize> $imp for CheckedArray<T, N>
+            where
+                Checked<T>: $imp<Output = Checked<T>>,
+            {
+                type Output = CheckedArray<T, N>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self) -> CheckedArray<T, N> {
+                    let mut result = self;
+                    for x in result.0.iter_mut() {
+                        *x = $imp::$method(*x);
+                    }
+                    result
+                }
+            }
+
+            impl<'a, T: Copy, const N: usize> $imp for &'a CheckedArray<T, N>
+            where
+                Checked<T>: $imp<Output = Checked<T>>,
+            {
+                type Output = CheckedArray<T, N>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self) -> CheckedArray<T, N> {
+                    $imp::$method(*self)
+                }
+            }
+        )*
+    };
+}
+
+impl_array_unop! {
+    Neg, neg;
+    Not, not;
+}
+
+// implements `T op CheckedArray<T, N>` for the primitives, which can't be done generically
+macro_rules! impl_array_reverse {
+    ($($t:ident)*) => {
+        $(
+            impl_array_reverse! { @ops $t: Add, add; Sub, sub; Mul, mul; Div, div; Rem, rem;
+                BitAnd, bitand; BitOr, bitor; BitXor, bitxor; }
+        )*
+    };
+    (@ops $t:ident: $($imp:ident, $method:ident;)*) => {
+        $(
+            impl<const N: usize> $imp<CheckedArray<$t, N>> for $t {
+                type Output = CheckedArray<$t, N>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: CheckedArray<$t, N>) -> CheckedArray<$t, N> {
+                    let mut result = other;
+                    for x in result.0.iter_mut() {
+                        *x = $imp::$method(Checked::new(self), *x);
+                    }
+                    result
+                }
+            }
+        )*
+    };
+}
+
+impl_array_reverse! { u8 u16 u32 u64 usize i8 i16 i32 i64 isize }
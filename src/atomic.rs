@@ -0,0 +1,331 @@
+//! Atomic integers whose read-modify-write operations fail instead of wrapping.
+//!
+//! `AtomicU64::fetch_add` wraps on overflow, which turns a leaked reference count or a runaway
+//! byte count into a small number without anyone noticing. The operations of
+//! [`AtomicChecked<T>`](struct.AtomicChecked.html) work out the new value with the operators of
+//! `Checked` in a compare-exchange loop, and leave the value alone when that fails.
+//!
+//! This needs atomic compare-and-swap, so the module is only there on targets that have it, and
+//! each type only where its size does.
+//! # Examples
+//! ```
+//! use checked::AtomicChecked;
+//! use std::sync::atomic::Ordering;
+//! use std::sync::Arc;
+//! use std::thread;
+//!
+//! let in_flight = Arc::new(AtomicChecked::new(0_u16));
+//! let handles: Vec<_> = (0..4)
+//!     .map(|_| {
+//!         let in_flight = in_flight.clone();
+//!         thread::spawn(move || {
+//!             for _ in 0..200 {
+//!                 in_flight.fetch_add(100, Ordering::Relaxed).ok();
+//!             }
+//!         })
+//!     })
+//!     .collect();
+//! for handle in handles {
+//!     handle.join().unwrap();
+//! }
+//! // The additions that would have passed `u16::MAX` failed, and the rest went through.
+//! assert_eq!(*in_flight.load(Ordering::Relaxed), Some(65_500));
+//! ```
+
+use core::fmt;
+use core::ops::{Add, Mul, Sub};
+use core::sync::atomic::*;
+
+use num::Checked;
+
+/// An integer type with a matching atomic type.
+pub trait Atomic: Copy {
+    /// The atomic type, such as `AtomicU32` for `u32`.
+    type Atomic;
+
+    /// Creates the atomic type with the given value.
+    fn new_atomic(x: Self) -> Self::Atomic;
+
+    /// Loads the value.
+    fn load(atomic: &Self::Atomic, order: Ordering) -> Self;
+
+    /// Stores a value.
+    fn store(atomic: &Self::Atomic, x: Self, order: Ordering);
+
+    /// Stores `new` if the value is `current`, returning the previous value, and may fail
+    /// spuriously.
+    fn compare_exchange_weak(
+        atomic: &Self::Atomic,
+        current: Self,
+        new: Self,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<Self, Self>;
+
+    /// Takes the value out.
+    fn into_inner(atomic: Self::Atomic) -> Self;
+}
+
+macro_rules! impl_atomic {
+    ($($t:ident => $a:ident: $size:tt,)*) => {
+        $(
+            #[cfg(target_has_atomic = $size)]
+            impl Atomic for $t {
+                type Atomic = $a;
+
+                #[inline]
+                fn new_atomic(x: $t) -> $a {
+                    $a::new(x)
+                }
+
+                #[inline]
+                fn load(atomic: &$a, order: Ordering) -> $t {
+                    atomic.load(order)
+                }
+
+                #[inline]
+                fn store(atomic: &$a, x: $t, order: Ordering) {
+                    atomic.store(x, order)
+                }
+
+                #[inline]
+                fn compare_exchange_weak(
+                    atomic: &$a,
+                    current: $t,
+                    new: $t,
+                    success: Ordering,
+                    failure: Ordering,
+                ) -> Result<$t, $t> {
+                    atomic.compare_exchange_weak(current, new, success, failure)
+                }
+
+                #[inline]
+                fn into_inner(atomic: $a) -> $t {
+                    atomic.into_inner()
+                }
+            }
+        )*
+    };
+}
+
+// Each type is only there on targets that can compare-and-swap values of its size.
+impl_atomic! {
+    u8 => AtomicU8: "8", u16 => AtomicU16: "16", u32 => AtomicU32: "32", u64 => AtomicU64: "64",
+    usize => AtomicUsize: "ptr",
+    i8 => AtomicI8: "8", i16 => AtomicI16: "16", i32 => AtomicI32: "32", i64 => AtomicI64: "64",
+    isize => AtomicIsize: "ptr",
+}
+
+/// An atomic integer with checked read-modify-write operations.
+///
+/// [`fetch_add`](#method.fetch_add), [`fetch_sub`](#method.fetch_sub),
+/// [`fetch_mul`](#method.fetch_mul) and [`fetch_update`](#method.fetch_update) return
+/// `Ok` with the previous value when they store the new one, or `Err` with the current value,
+/// unchanged, when the operation fails. Their results come from the operators of `Checked`, so
+/// failures are reported to the [hooks](../hook/index.html).
+///
+/// One made with [`sticky`](#method.sticky) is *poisoned* by its first failure: from then on it
+/// loads as failed and every operation fails, until [`clear_poison`](#method.clear_poison).
+/// Operations that were already running on other threads when it was poisoned may still
+/// complete.
+/// # Examples
+/// ```
+/// use checked::{AtomicChecked, Checked};
+/// use std::sync::atomic::Ordering::SeqCst;
+///
+/// let refs = AtomicChecked::new(1_u32);
+/// assert_eq!(refs.fetch_sub(1, SeqCst), Ok(1));
+/// assert_eq!(refs.fetch_sub(1, SeqCst), Err(0));
+/// assert_eq!(refs.load(SeqCst), Checked::new(0));
+///
+/// let bytes = AtomicChecked::sticky(u32::MAX - 10);
+/// assert_eq!(bytes.fetch_add(20, SeqCst), Err(u32::MAX - 10));
+/// assert_eq!(bytes.fetch_sub(20, SeqCst), Err(u32::MAX - 10));
+/// assert_eq!(bytes.load(SeqCst), Checked::OVERFLOW);
+/// ```
+pub struct AtomicChecked<T: Atomic> {
+    value: T::Atomic,
+    sticky: bool,
+    poisoned: AtomicBool,
+}
+
+impl<T: Atomic> AtomicChecked<T> {
+    /// Creates a new AtomicChecked instance, which fails each operation on its own.
+    #[inline]
+    pub fn new(x: T) -> AtomicChecked<T> {
+        AtomicChecked {
+            value: T::new_atomic(x),
+            sticky: false,
+            poisoned: AtomicBool::new(false),
+        }
+    }
+
+    /// Creates a new AtomicChecked instance that is poisoned by its first failure.
+    #[inline]
+    pub fn sticky(x: T) -> AtomicChecked<T> {
+        AtomicChecked {
+            sticky: true,
+            ..AtomicChecked::new(x)
+        }
+    }
+
+    /// Whether a failure has poisoned the value.
+    #[inline]
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned.load(Ordering::Acquire)
+    }
+
+    /// Lets the operations go through again after the value was poisoned.
+    #[inline]
+    pub fn clear_poison(&self) {
+        self.poisoned.store(false, Ordering::Release);
+    }
+
+    /// Loads the value, or `None` if it is poisoned.
+    #[inline]
+    pub fn load(&self, order: Ordering) -> Checked<T> {
+        if self.is_poisoned() {
+            Checked(None)
+        } else {
+            Checked(Some(T::load(&self.value, order)))
+        }
+    }
+
+    /// Stores a value, which doesn't clear the poison.
+    #[inline]
+    pub fn store(&self, x: T, order: Ordering) {
+        T::store(&self.value, x, order)
+    }
+
+    /// Consumes the atomic and returns the value, or `None` if it is poisoned.
+    #[inline]
+    pub fn into_inner(self) -> Checked<T> {
+        if self.poisoned.into_inner() {
+            Checked(None)
+        } else {
+            Checked(Some(T::into_inner(self.value)))
+        }
+    }
+
+    /// Replaces the value with `f` of it, unless `f` fails.
+    ///
+    /// `f` may be called more than once if other threads change the value in between, like the
+    /// `fetch_update` of the standard atomics. `set_order` is the ordering for storing the new
+    /// value, and `fetch_order` for loading the current one.
+    #[track_caller]
+    pub fn fetch_update<F>(
+        &self,
+        set_order: Ordering,
+        fetch_order: Ordering,
+        mut f: F,
+    ) -> Result<T, T>
+    where
+        F: FnMut(T) -> Checked<T>,
+    {
+        let mut current = T::load(&self.value, fetch_order);
+        loop {
+            if self.is_poisoned() {
+                return Err(current);
+            }
+            let new = f(current);
+            if let Some(result) = self.exchange(&mut current, new, set_order, fetch_order) {
+                return result;
+            }
+        }
+    }
+
+    // Stores `new` in place of `current`, and returns the result, unless another thread changed
+    // the value in between, in which case `current` is updated to try again.
+    #[inline]
+    fn exchange(
+        &self,
+        current: &mut T,
+        new: Checked<T>,
+        set_order: Ordering,
+        fetch_order: Ordering,
+    ) -> Option<Result<T, T>> {
+        match new.0 {
+            Some(new) => {
+                match T::compare_exchange_weak(&self.value, *current, new, set_order, fetch_order) {
+                    Ok(previous) => Some(Ok(previous)),
+                    Err(actual) => {
+                        *current = actual;
+                        None
+                    }
+                }
+            }
+            None => {
+                if self.sticky {
+                    self.poisoned.store(true, Ordering::Release);
+                }
+                Some(Err(*current))
+            }
+        }
+    }
+}
+
+// implements the `fetch_*` operations by the operators of `Checked`, which are applied here
+// rather than in a closure for `fetch_update`, so that the hooks get the caller's location
+macro_rules! impl_atomic_fetch {
+    ($($method:ident, $imp:ident, $op:ident, $doc:expr;)*) => {
+        $(
+            impl<T: Atomic> AtomicChecked<T>
+            where
+                Checked<T>: $imp<T, Output = Checked<T>>,
+            {
+                #[doc = $doc]
+                #[inline]
+                #[track_caller]
+                pub fn $method(&self, x: T, order: Ordering) -> Result<T, T> {
+                    let fetch_order = load_order(order);
+                    let mut current = T::load(&self.value, fetch_order);
+                    loop {
+                        if self.is_poisoned() {
+                            return Err(current);
+                        }
+                        let new = $imp::$op(Checked::new(current), x);
+                        if let Some(result) = self.exchange(&mut current, new, order, fetch_order) {
+                            return result;
+                        }
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_atomic_fetch! {
+    fetch_add, Add, add, "Adds to the value, unless the sum overflows.";
+    fetch_sub, Sub, sub, "Subtracts from the value, unless the difference overflows.";
+    fetch_mul, Mul, mul, "Multiplies the value, unless the product overflows.";
+}
+
+// The strongest ordering that a load can use as part of an operation with `order`.
+#[inline]
+fn load_order(order: Ordering) -> Ordering {
+    match order {
+        Ordering::Release => Ordering::Relaxed,
+        Ordering::AcqRel => Ordering::Acquire,
+        order => order,
+    }
+}
+
+impl<T: Atomic + Default> Default for AtomicChecked<T> {
+    #[inline]
+    fn default() -> AtomicChecked<T> {
+        AtomicChecked::new(T::default())
+    }
+}
+
+impl<T: Atomic> From<T> for AtomicChecked<T> {
+    #[inline]
+    fn from(x: T) -> AtomicChecked<T> {
+        AtomicChecked::new(x)
+    }
+}
+
+impl<T: Atomic + fmt::Debug> fmt::Debug for AtomicChecked<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.load(Ordering::Relaxed), f)
+    }
+}
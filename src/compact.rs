@@ -0,0 +1,496 @@
+//! A checked integer the size of the integer, which uses one of its values for the failed state.
+//!
+//! `Checked<u64>` is an `Option<u64>`, which takes 16 bytes. A
+//! [`CompactChecked<u64>`](struct.CompactChecked.html) takes 8, because it gives up one value of
+//! `u64`, the *sentinel*, to mean that it failed. The sentinel is `MAX` by default, or `MIN` with
+//! [`Min`](struct.Min.html), or any value by implementing [`Sentinel`](trait.Sentinel.html).
+//! # Examples
+//! ```
+//! use checked::compact::{CompactChecked, Sentinel};
+//! use std::mem::size_of;
+//!
+//! assert_eq!(size_of::<CompactChecked<u64>>(), size_of::<u64>());
+//!
+//! // -1 marks a failure, so all the other values are usable.
+//! struct MinusOne;
+//!
+//! impl Sentinel<i32> for MinusOne {
+//!     const VALUE: i32 = -1;
+//! }
+//!
+//! let offset = CompactChecked::<i32, MinusOne>::new(4096);
+//! assert_eq!((offset * 2).get(), Some(8192));
+//! assert_eq!((offset - 4097).get(), None);
+//! ```
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::ops::*;
+
+use num_traits::{One, Zero};
+
+use num::{fmt_overflow, Checked};
+
+/// The value that a [`CompactChecked`](struct.CompactChecked.html) uses for its failed state.
+pub trait Sentinel<T> {
+    /// The sentinel value.
+    const VALUE: T;
+}
+
+/// The sentinel `T::MAX`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Max;
+
+/// The sentinel `T::MIN`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Min;
+
+macro_rules! impl_sentinel {
+    ($($t:ident)*) => {
+        $(
+            impl Sentinel<$t> for Max {
+                const VALUE: $t = $t::MAX;
+            }
+
+            impl Sentinel<$t> for Min {
+                const VALUE: $t = $t::MIN;
+            }
+        )*
+    };
+}
+
+impl_sentinel! { u8 u16 u32 u64 usize i8 i16 i32 i64 isize }
+
+/// A checked integer that is the same size as `T`, with the value `S::VALUE` as its failed state.
+///
+/// It has the same operators as `Checked`, and works out their results with them. The difference
+/// is that the sentinel can't be a valid value, so a result that equals the sentinel is a failure
+/// too: with the default sentinel, `CompactChecked::<u8>::new(254) + 1` fails.
+///
+/// See the [module documentation](index.html) for choosing the sentinel.
+/// # Examples
+/// ```
+/// use checked::compact::{CompactChecked, Min};
+/// use checked::Checked;
+///
+/// let counters = [CompactChecked::<u32>::new(7); 4];
+/// let total = counters.iter().fold(CompactChecked::new(0), |sum, &x| sum + x);
+/// assert_eq!(Checked::from(total), Checked::new(28));
+///
+/// // With `Min`, `MAX` is a valid value but `MIN` isn't.
+/// let x = CompactChecked::<i16, Min>::new(i16::MAX);
+/// assert_eq!(x.get(), Some(i16::MAX));
+/// assert_eq!((-x - 1).get(), None);
+/// ```
+#[repr(transparent)]
+pub struct CompactChecked<T, S = Max> {
+    raw: T,
+    sentinel: PhantomData<fn() -> S>,
+}
+
+impl<T, S: Sentinel<T>> CompactChecked<T, S> {
+    /// The failed state.
+    pub const OVERFLOW: CompactChecked<T, S> = CompactChecked {
+        raw: S::VALUE,
+        sentinel: PhantomData,
+    };
+
+    /// Makes a value from its representation, in which the sentinel is the failed state.
+    #[inline]
+    pub const fn from_raw(raw: T) -> CompactChecked<T, S> {
+        CompactChecked {
+            raw,
+            sentinel: PhantomData,
+        }
+    }
+
+    /// The representation, in which the sentinel is the failed state.
+    #[inline]
+    pub fn into_raw(self) -> T {
+        self.raw
+    }
+}
+
+impl<T: PartialEq, S: Sentinel<T>> CompactChecked<T, S> {
+    /// Creates a new CompactChecked instance.
+    /// This is in the failed state if `x` is the sentinel.
+    #[inline]
+    pub fn new(x: T) -> CompactChecked<T, S> {
+        CompactChecked::from_raw(x)
+    }
+
+    /// Whether the value is valid, rather than failed.
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.raw != S::VALUE
+    }
+
+    /// The value, or `None` in the failed state.
+    #[inline]
+    pub fn get(self) -> Option<T> {
+        if self.is_valid() {
+            Some(self.raw)
+        } else {
+            None
+        }
+    }
+
+    /// Converts to `Checked`. This is the same as `Checked::from`.
+    #[inline]
+    pub fn to_checked(self) -> Checked<T> {
+        Checked(self.get())
+    }
+}
+
+// These are written out because deriving them would require `S` to implement them too.
+impl<T: Clone, S> Clone for CompactChecked<T, S> {
+    #[inline]
+    fn clone(&self) -> CompactChecked<T, S> {
+        CompactChecked {
+            raw: self.raw.clone(),
+            sentinel: PhantomData,
+        }
+    }
+}
+
+impl<T: Copy, S> Copy for CompactChecked<T, S> {}
+
+// Every failed value has the same representation, so comparing it compares the states too.
+impl<T: PartialEq, S> PartialEq for CompactChecked<T, S> {
+    #[inline]
+    fn eq(&self, other: &CompactChecked<T, S>) -> bool {
+        self.raw == other.raw
+    }
+}
+
+impl<T: Eq, S> Eq for CompactChecked<T, S> {}
+
+impl<T: Hash, S> Hash for CompactChecked<T, S> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.raw.hash(state)
+    }
+}
+
+impl<T: PartialOrd + Copy, S: Sentinel<T>> PartialOrd for CompactChecked<T, S> {
+    fn partial_cmp(&self, other: &CompactChecked<T, S>) -> Option<Ordering> {
+        // As for `Checked`, the failed state can't be compared.
+        match (self.get(), other.get()) {
+            (Some(x), Some(y)) => PartialOrd::partial_cmp(&x, &y),
+            _ => None,
+        }
+    }
+}
+
+impl<T, S: Sentinel<T>> Default for CompactChecked<T, S> {
+    #[inline]
+    fn default() -> CompactChecked<T, S> {
+        CompactChecked::OVERFLOW
+    }
+}
+
+impl<T: fmt::Debug + PartialEq, S: Sentinel<T>> fmt::Debug for CompactChecked<T, S> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_valid() {
+            self.raw.fmt(f)
+        } else {
+            fmt_overflow(f, true)
+        }
+    }
+}
+
+impl<T: fmt::Display + PartialEq, S: Sentinel<T>> fmt::Display for CompactChecked<T, S> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_valid() {
+            self.raw.fmt(f)
+        } else {
+            fmt_overflow(f, false)
+        }
+    }
+}
+
+// implements the radix and exponent formatting traits
+macro_rules! impl_compact_fmt {
+    ($($imp:ident)*) => {
+        $(
+            impl<T: fmt::$imp + PartialEq, S: Sentinel<T>> fmt::$imp for CompactChecked<T, S> {
+                #[inline]
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    if self.is_valid() {
+                        self.raw.fmt(f)
+                    } else {
+                        fmt_overflow(f, false)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_compact_fmt! { LowerHex UpperHex Octal Binary LowerExp UpperExp }
+
+impl<T: PartialEq, S: Sentinel<T>> From<T> for CompactChecked<T, S> {
+    #[inline]
+    fn from(x: T) -> CompactChecked<T, S> {
+        CompactChecked::new(x)
+    }
+}
+
+impl<T: PartialEq, S: Sentinel<T>> From<Checked<T>> for CompactChecked<T, S> {
+    /// Converts from `Checked`. A valid value that is the sentinel becomes the failed state.
+    #[inline]
+    fn from(x: Checked<T>) -> CompactChecked<T, S> {
+        match x.0 {
+            Some(x) => CompactChecked::new(x),
+            None => CompactChecked::OVERFLOW,
+        }
+    }
+}
+
+impl<T: PartialEq, S: Sentinel<T>> From<CompactChecked<T, S>> for Checked<T> {
+    #[inline]
+    fn from(x: CompactChecked<T, S>) -> Checked<T> {
+        x.to_checked()
+    }
+}
+
+impl<T, S> Zero for CompactChecked<T, S>
+where
+    T: Zero + PartialEq,
+    S: Sentinel<T>,
+    CompactChecked<T, S>: Add<Output = CompactChecked<T, S>>,
+{
+    fn zero() -> CompactChecked<T, S> {
+        CompactChecked::new(T::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.raw.is_zero() && self.is_valid()
+    }
+}
+
+impl<T, S> One for CompactChecked<T, S>
+where
+    T: One + PartialEq,
+    S: Sentinel<T>,
+    CompactChecked<T, S>: Mul<Output = CompactChecked<T, S>>,
+{
+    fn one() -> CompactChecked<T, S> {
+        CompactChecked::new(T::one())
+    }
+}
+
+// implements binary operators by those of `Checked`, including the forms with references
+macro_rules! impl_compact_binop {
+    ($($imp:ident, $method:ident, $imp_assign:ident, $method_assign:ident;)*) => {
+        $(
+            impl<T, S> $imp for CompactChecked<T, S>
+            where
+                T: Copy + PartialEq,
+                S: Sentinel<T>,
+                Checked<T>: $imp<Output = Checked<T>>,
+            {
+                type Output = CompactChecked<T, S>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: CompactChecked<T, S>) -> CompactChecked<T, S> {
+                    CompactChecked::from($imp::$method(self.to_checked(), other.to_checked()))
+                }
+            }
+
+            impl<T, S> $imp<T> for CompactChecked<T, S>
+            where
+                T: Copy + PartialEq,
+                S: Sentinel<T>,
+                Checked<T>: $imp<Output = Checked<T>>,
+            {
+                type Output = CompactChecked<T, S>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: T) -> CompactChecked<T, S> {
+                    $imp::$method(self, CompactChecked::new(other))
+                }
+            }
+
+            impl<'a, T, S> $imp<&'a CompactChecked<T, S>> for CompactChecked<T, S>
+            where
+                T: Copy + PartialEq,
+                S: Sentinel<T>,
+                Checked<T>: $imp<Output = Checked<T>>,
+            {
+                type Output = CompactChecked<T, S>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: &'a CompactChecked<T, S>) -> CompactChecked<T, S> {
+                    $imp::$method(self, *other)
+                }
+            }
+
+            impl<'a, T, S, U> $imp<U> for &'a CompactChecked<T, S>
+            where
+                T: Copy,
+                CompactChecked<T, S>: $imp<U, Output = CompactChecked<T, S>>,
+            {
+                type Output = CompactChecked<T, S>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: U) -> CompactChecked<T, S> {
+                    $imp::$method(*self, other)
+                }
+            }
+
+            impl<T, S, U> $imp_assign<U> for CompactChecked<T, S>
+            where
+                T: Copy,
+                CompactChecked<T, S>: $imp<U, Output = CompactChecked<T, S>>,
+            {
+                #[inline]
+                #[track_caller]
+                fn $method_assign(&mut self, other: U) {
+                    *self = $imp::$method(*self, other);
+                }
+            }
+        )*
+    };
+}
+
+impl_compact_binop! {
+    Add, add, AddAssign, add_assign;
+    Sub, sub, SubAssign, sub_assign;
+    Mul, mul, MulAssign, mul_assign;
+    Div, div, DivAssign, div_assign;
+    Rem, rem, RemAssign, rem_assign;
+    BitAnd, bitand, BitAndAssign, bitand_assign;
+    BitOr, bitor, BitOrAssign, bitor_assign;
+    BitXor, bitxor, BitXorAssign, bitxor_assign;
+}
+
+// implements shifts by `u32`, as for `Checked`
+macro_rules! impl_compact_sh {
+    ($($imp:ident, $method:ident, $imp_assign:ident, $method_assign:ident;)*) => {
+        $(
+            impl<T, S> $imp<u32> for CompactChecked<T, S>
+            where
+                T: Copy + PartialEq,
+                S: Sentinel<T>,
+                Checked<T>: $imp<u32, Output = Checked<T>>,
+            {
+                type Output = CompactChecked<T, S>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: u32) -> CompactChecked<T, S> {
+                    CompactChecked::from($imp::$method(self.to_checked(), other))
+                }
+            }
+
+            impl<'a, T, S> $imp<u32> for &'a CompactChecked<T, S>
+            where
+                T: Copy + PartialEq,
+                S: Sentinel<T>,
+                Checked<T>: $imp<u32, Output = Checked<T>>,
+            {
+                type Output = CompactChecked<T, S>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: u32) -> CompactChecked<T, S> {
+                    $imp::$method(*self, other)
+                }
+            }
+
+            impl<T, S> $imp_assign<u32> for CompactChecked<T, S>
+            where
+                T: Copy + PartialEq,
+                S: Sentinel<T>,
+                Checked<T>: $imp<u32, Output = Checked<T>>,
+            {
+                #[inline]
+                #[track_caller]
+                fn $method_assign(&mut self, other: u32) {
+                    *self = $imp::$method(*self, other);
+                }
+            }
+        )*
+    };
+}
+
+impl_compact_sh! {
+    Shl, shl, ShlAssign, shl_assign;
+    Shr, shr, ShrAssign, shr_assign;
+}
+
+// implements unary operators by those of `Checked`
+macro_rules! impl_compact_unop {
+    ($($imp:ident, $method:ident;)*) => {
+        $(
+            impl<T, S> $imp for CompactChecked<T, S>
+            where
+                T: Copy + PartialEq,
+                S: Sentinel<T>,
+                Checked<T>: $imp<Output = Checked<T>>,
+            {
+                type Output = CompactChecked<T, S>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self) -> CompactChecked<T, S> {
+                    CompactChecked::from($imp::$method(self.to_checked()))
+                }
+            }
+
+            impl<'a, T, S> $imp for &'a CompactChecked<T, S>
+            where
+                T: Copy + PartialEq,
+                S: Sentinel<T>,
+                Checked<T>: $imp<Output = Checked<T>>,
+            {
+                type Output = CompactChecked<T, S>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self) -> CompactChecked<T, S> {
+                    $imp::$method(*self)
+                }
+            }
+        )*
+    };
+}
+
+impl_compact_unop! {
+    Neg, neg;
+    Not, not;
+}
+
+// implements `T op CompactChecked<T, S>` for the primitives, which can't be done generically
+macro_rules! impl_compact_reverse {
+    ($($t:ident)*) => {
+        $(
+            impl_compact_reverse! { @ops $t: Add, add; Sub, sub; Mul, mul; Div, div; Rem, rem;
+                BitAnd, bitand; BitOr, bitor; BitXor, bitxor; }
+        )*
+    };
+    (@ops $t:ident: $($imp:ident, $method:ident;)*) => {
+        $(
+            impl<S: Sentinel<$t>> $imp<CompactChecked<$t, S>> for $t {
+                type Output = CompactChecked<$t, S>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: CompactChecked<$t, S>) -> CompactChecked<$t, S> {
+                    $imp::$method(CompactChecked::new(self), other)
+                }
+            }
+        )*
+    };
+}
+
+impl_compact_reverse! { u8 u16 u32 u64 usize i8 i16 i32 i64 isize }
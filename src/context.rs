@@ -0,0 +1,398 @@
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::mem;
+use core::ops::*;
+use std::borrow::Cow;
+use std::vec::Vec;
+
+use num::Checked;
+
+/// A checked value that can explain its failure.
+///
+/// This is what [`Checked::context`](struct.Checked.html#method.context) returns. It has the
+/// operators of `Checked`, and a failed value keeps the messages attached to it as it goes
+/// through them, collecting more from each `context` call on the way. `Display` and `Debug`
+/// show them, most recent first, so a failure that surfaces far from its cause still says where
+/// it came from. A successful value has no messages, and attaching one does nothing.
+///
+/// Comparing and hashing only look at the value, like for `Checked`, so failures with different
+/// messages are equal.
+///
+/// This needs the `std` feature.
+/// # Examples
+/// ```
+/// use checked::Checked;
+///
+/// let (width, height) = (100_000_u32, 100_000_u32);
+/// let pixels = (Checked::new(width) * height).context("computing the frame buffer size");
+/// let bytes = (pixels * 4).with_context(|| format!("allocating {} frames", 3));
+///
+/// assert_eq!(
+///     bytes.to_string(),
+///     "allocating 3 frames: computing the frame buffer size: overflow",
+/// );
+/// ```
+#[derive(Clone)]
+pub struct WithContext<T> {
+    value: Option<T>,
+    // innermost first
+    context: Vec<Cow<'static, str>>,
+}
+
+impl<T> Checked<T> {
+    /// Attaches a message to a failed value, to say what it was part of.
+    /// This does nothing to a successful value.
+    #[inline]
+    pub fn context<M>(self, message: M) -> WithContext<T>
+    where
+        M: Into<Cow<'static, str>>,
+    {
+        WithContext::from(self).context(message)
+    }
+
+    /// Like [`context`](#method.context), but the message is only made if the value failed.
+    #[inline]
+    pub fn with_context<M, F>(self, message: F) -> WithContext<T>
+    where
+        M: Into<Cow<'static, str>>,
+        F: FnOnce() -> M,
+    {
+        WithContext::from(self).with_context(message)
+    }
+}
+
+impl<T> WithContext<T> {
+    /// Attaches another message to a failed value. This does nothing to a successful value.
+    #[inline]
+    pub fn context<M>(self, message: M) -> WithContext<T>
+    where
+        M: Into<Cow<'static, str>>,
+    {
+        self.with_context(|| message)
+    }
+
+    /// Like [`context`](#method.context), but the message is only made if the value failed.
+    pub fn with_context<M, F>(mut self, message: F) -> WithContext<T>
+    where
+        M: Into<Cow<'static, str>>,
+        F: FnOnce() -> M,
+    {
+        if self.value.is_none() {
+            self.context.push(message().into());
+        }
+        self
+    }
+
+    /// The messages attached to a failed value, most recent first.
+    /// # Examples
+    /// ```
+    /// use checked::Checked;
+    ///
+    /// let x = Checked::<u8>::OVERFLOW.context("reading the header").context("opening the file");
+    /// assert_eq!(x.messages().collect::<Vec<_>>(), ["opening the file", "reading the header"]);
+    /// ```
+    pub fn messages(&self) -> impl Iterator<Item = &str> {
+        self.context.iter().rev().map(|message| &**message)
+    }
+
+    // The result of an operation, with the messages of its operands if it failed.
+    fn merge(
+        value: Checked<T>,
+        mut context: Vec<Cow<'static, str>>,
+        other: Vec<Cow<'static, str>>,
+    ) -> WithContext<T> {
+        if value.is_none() {
+            context.extend(other);
+        } else {
+            context.clear();
+        }
+        WithContext {
+            value: value.0,
+            context,
+        }
+    }
+
+    // A placeholder to leave behind while an assignment operator takes the value out.
+    const EMPTY: WithContext<T> = WithContext {
+        value: None,
+        context: Vec::new(),
+    };
+}
+
+impl<T: PartialEq> PartialEq for WithContext<T> {
+    #[inline]
+    fn eq(&self, other: &WithContext<T>) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Eq> Eq for WithContext<T> {}
+
+impl<T: Hash> Hash for WithContext<T> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state)
+    }
+}
+
+impl<T> Deref for WithContext<T> {
+    type Target = Option<T>;
+
+    #[inline]
+    fn deref(&self) -> &Option<T> {
+        &self.value
+    }
+}
+
+impl<T> From<T> for WithContext<T> {
+    #[inline]
+    fn from(x: T) -> WithContext<T> {
+        WithContext::from(Checked::new(x))
+    }
+}
+
+impl<T> From<Checked<T>> for WithContext<T> {
+    #[inline]
+    fn from(x: Checked<T>) -> WithContext<T> {
+        WithContext {
+            value: x.0,
+            context: Vec::new(),
+        }
+    }
+}
+
+impl<T> From<WithContext<T>> for Checked<T> {
+    #[inline]
+    fn from(x: WithContext<T>) -> Checked<T> {
+        Checked(x.value)
+    }
+}
+
+impl<T> WithContext<T> {
+    fn fmt_failure(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for message in self.messages() {
+            write!(f, "{}: ", message)?;
+        }
+        f.write_str("overflow")
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for WithContext<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.value {
+            Some(ref x) => x.fmt(f),
+            None => {
+                // quoted, like the failure of `Checked`
+                f.write_str("\"")?;
+                self.fmt_failure(f)?;
+                f.write_str("\"")
+            }
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for WithContext<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.value {
+            Some(ref x) => x.fmt(f),
+            None => self.fmt_failure(f),
+        }
+    }
+}
+
+// implements binary operators by those of `Checked`, including the forms with references
+macro_rules! impl_context_binop {
+    ($($imp:ident, $method:ident, $imp_assign:ident, $method_assign:ident;)*) => {
+        $(
+            impl<T> $imp for WithContext<T>
+            where
+                Checked<T>: $imp<Output = Checked<T>>,
+            {
+                type Output = WithContext<T>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: WithContext<T>) -> WithContext<T> {
+                    let value = $imp::$method(Checked(self.value), Checked(other.value));
+                    WithContext::merge(value, self.context, other.context)
+                }
+            }
+
+            impl<T> $imp<T> for WithContext<T>
+            where
+                Checked<T>: $imp<T, Output = Checked<T>>,
+            {
+                type Output = WithContext<T>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: T) -> WithContext<T> {
+                    let value = $imp::$method(Checked(self.value), other);
+                    WithContext::merge(value, self.context, Vec::new())
+                }
+            }
+
+            impl<'a, T: Clone> $imp<&'a WithContext<T>> for WithContext<T>
+            where
+                Checked<T>: $imp<Output = Checked<T>>,
+            {
+                type Output = WithContext<T>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: &'a WithContext<T>) -> WithContext<T> {
+                    $imp::$method(self, other.clone())
+                }
+            }
+
+            impl<'a, T: Clone, U> $imp<U> for &'a WithContext<T>
+            where
+                WithContext<T>: $imp<U, Output = WithContext<T>>,
+            {
+                type Output = WithContext<T>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: U) -> WithContext<T> {
+                    $imp::$method(self.clone(), other)
+                }
+            }
+
+            impl<T, U> $imp_assign<U> for WithContext<T>
+            where
+                WithContext<T>: $imp<U, Output = WithContext<T>>,
+            {
+                #[inline]
+                #[track_caller]
+                fn $method_assign(&mut self, other: U) {
+                    *self = $imp::$method(mem::replace(self, WithContext::EMPTY), other);
+                }
+            }
+        )*
+    };
+}
+
+impl_context_binop! {
+    Add, add, AddAssign, add_assign;
+    Sub, sub, SubAssign, sub_assign;
+    Mul, mul, MulAssign, mul_assign;
+    Div, div, DivAssign, div_assign;
+    Rem, rem, RemAssign, rem_assign;
+    BitAnd, bitand, BitAndAssign, bitand_assign;
+    BitOr, bitor, BitOrAssign, bitor_assign;
+    BitXor, bitxor, BitXorAssign, bitxor_assign;
+}
+
+// implements shifts by `u32`, as for `Checked`
+macro_rules! impl_context_sh {
+    ($($imp:ident, $method:ident, $imp_assign:ident, $method_assign:ident;)*) => {
+        $(
+            impl<T> $imp<u32> for WithContext<T>
+            where
+                Checked<T>: $imp<u32, Output = Checked<T>>,
+            {
+                type Output = WithContext<T>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: u32) -> WithContext<T> {
+                    let value = $imp::$method(Checked(self.value), other);
+                    WithContext::merge(value, self.context, Vec::new())
+                }
+            }
+
+            impl<'a, T: Clone> $imp<u32> for &'a WithContext<T>
+            where
+                Checked<T>: $imp<u32, Output = Checked<T>>,
+            {
+                type Output = WithContext<T>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: u32) -> WithContext<T> {
+                    $imp::$method(self.clone(), other)
+                }
+            }
+
+            impl<T> $imp_assign<u32> for WithContext<T>
+            where
+                Checked<T>: $imp<u32, Output = Checked<T>>,
+            {
+                #[inline]
+                #[track_caller]
+                fn $method_assign(&mut self, other: u32) {
+                    *self = $imp::$method(mem::replace(self, WithContext::EMPTY), other);
+                }
+            }
+        )*
+    };
+}
+
+impl_context_sh! {
+    Shl, shl, ShlAssign, shl_assign;
+    Shr, shr, ShrAssign, shr_assign;
+}
+
+// implements unary operators by those of `Checked`
+macro_rules! impl_context_unop {
+    ($($imp:ident, $method:ident;)*) => {
+        $(
+            impl<T> $imp for WithContext<T>
+            where
+                Checked<T>: $imp<Output = Checked<T>>,
+            {
+                type Output = WithContext<T>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self) -> WithContext<T> {
+                    let value = $imp::$method(Checked(self.value));
+                    WithContext::merge(value, self.context, Vec::new())
+                }
+            }
+
+            impl<'a, T: Clone> $imp for &'a WithContext<T>
+            where
+                Checked<T>: $imp<Output = Checked<T>>,
+            {
+                type Output = WithContext<T>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self) -> WithContext<T> {
+                    $imp::$method(self.clone())
+                }
+            }
+        )*
+    };
+}
+
+impl_context_unop! {
+    Neg, neg;
+    Not, not;
+}
+
+// implements `T op WithContext<T>` for the primitives, which can't be done generically
+macro_rules! impl_context_reverse {
+    ($($t:ident)*) => {
+        $(
+            impl_context_reverse! { @ops $t: Add, add; Sub, sub; Mul, mul; Div, div; Rem, rem;
+                BitAnd, bitand; BitOr, bitor; BitXor, bitxor; }
+        )*
+    };
+    (@ops $t:ident: $($imp:ident, $method:ident;)*) => {
+        $(
+            impl $imp<WithContext<$t>> for $t {
+                type Output = WithContext<$t>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: WithContext<$t>) -> WithContext<$t> {
+                    $imp::$method(WithContext::from(self), other)
+                }
+            }
+        )*
+    };
+}
+
+impl_context_reverse! { u8 u16 u32 u64 usize i8 i16 i32 i64 isize }
@@ -0,0 +1,649 @@
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops::*;
+
+use num_traits::{Bounded, One, Zero};
+
+use failure::{Op, OverflowError, Reason, Value};
+use hook::{self, Primitive};
+use num::{fmt_failure, Checked};
+
+/// Which way an operation failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// The true result was larger than the largest value of the type.
+    Positive,
+    /// The true result was smaller than the smallest value of the type.
+    Negative,
+    /// There was no true result (division by zero, for instance), or it isn't known which way
+    /// it went.
+    Undefined,
+}
+
+impl Direction {
+    /// A short description, such as "positive overflow".
+    pub fn description(self) -> &'static str {
+        match self {
+            Direction::Positive => "positive overflow",
+            Direction::Negative => "negative overflow",
+            Direction::Undefined => "overflow",
+        }
+    }
+}
+
+impl From<Reason> for Direction {
+    #[inline]
+    fn from(reason: Reason) -> Direction {
+        match reason {
+            Reason::PositiveOverflow => Direction::Positive,
+            Reason::NegativeOverflow => Direction::Negative,
+            Reason::DivisionByZero | Reason::ShiftOverflow => Direction::Undefined,
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+/// The failed state of a [`Directed`](struct.Directed.html) value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Overflow<T> {
+    /// Which way it failed.
+    pub direction: Direction,
+    /// The result with wrapping arithmetic, if it is known.
+    pub wrapped: Option<T>,
+}
+
+impl<T> Overflow<T> {
+    /// A failure with no known direction or wrapped result.
+    pub const UNDEFINED: Overflow<T> = Overflow {
+        direction: Direction::Undefined,
+        wrapped: None,
+    };
+}
+
+/// A checked integer whose failed state remembers which way it overflowed.
+///
+/// `Checked<T>` only knows that something failed. `Directed<T>` has the same operators, but its
+/// failed state also says whether the true result was too large, too small, or neither, and the
+/// wrapped result where there is one. That is enough to fall back to the saturated, clamped or
+/// wrapped value afterwards.
+///
+/// The direction carries through later operations as long as it's certain. For example, adding
+/// a positive number to a positive overflow is still a positive overflow. If it isn't certain, the
+/// direction becomes `Undefined`.
+/// The wrapped result carries through addition, subtraction, multiplication, negation, left
+/// shifts and the bitwise operators, which all wrap consistently.
+///
+/// As with `Checked<T>`, new failures are reported to the [hooks](hook/index.html), and with the
+/// `stats` feature the operations are counted in the [statistics](stats/index.html).
+/// # Examples
+/// ```
+/// use checked::{Direction, Directed};
+///
+/// let level = Directed::new(100_u8) * 3;
+/// assert_eq!(level.0.unwrap_err().direction, Direction::Positive);
+/// assert_eq!(level.unwrap_or_saturate(), 255);
+/// assert_eq!(level.unwrap_or_clamp(0, 200), 200);
+/// assert_eq!(level.unwrap_or_wrap(), 44);
+///
+/// let offset = Directed::new(-100_i8) - 100 - 50;
+/// assert_eq!(offset.unwrap_or_saturate(), -128);
+/// ```
+#[derive(PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Directed<T>(pub Result<T, Overflow<T>>);
+
+impl<T> Directed<T> {
+    /// Creates a new Directed instance. This is the same as `Directed(Ok(x))`.
+    #[inline]
+    pub const fn new(x: T) -> Directed<T> {
+        Directed(Ok(x))
+    }
+
+    /// The failed state, in no known direction.
+    pub const OVERFLOW: Directed<T> = Directed(Err(Overflow::UNDEFINED));
+
+    /// Which way the value failed, or `None` if it didn't.
+    #[inline]
+    pub fn direction(&self) -> Option<Direction> {
+        match self.0 {
+            Ok(_) => None,
+            Err(ref e) => Some(e.direction),
+        }
+    }
+
+    /// Converts to a `Result`, with the direction of a failure as the error's reason.
+    /// # Examples
+    /// ```
+    /// use checked::{Directed, Reason};
+    ///
+    /// let error = (Directed::new(0_u32) - 1).into_result().unwrap_err();
+    /// assert_eq!(error.reason, Some(Reason::NegativeOverflow));
+    /// assert_eq!(error.to_string(), "u32 arithmetic failed (negative overflow)");
+    /// ```
+    #[inline]
+    pub fn into_result(self) -> Result<T, OverflowError> {
+        match self.0 {
+            Ok(x) => Ok(x),
+            Err(e) => Err(OverflowError {
+                reason: match e.direction {
+                    Direction::Positive => Some(Reason::PositiveOverflow),
+                    Direction::Negative => Some(Reason::NegativeOverflow),
+                    Direction::Undefined => None,
+                },
+                ..OverflowError::new::<T>()
+            }),
+        }
+    }
+
+    /// Returns the value, or the wrapped result if it failed.
+    /// # Panics
+    /// Panics if it failed and there is no wrapped result, as after a division by zero.
+    #[inline]
+    #[track_caller]
+    pub fn unwrap_or_wrap(self) -> T {
+        match self.0 {
+            Ok(x) => x,
+            Err(Overflow {
+                wrapped: Some(x), ..
+            }) => x,
+            Err(_) => panic!("no wrapped result for the failed value"),
+        }
+    }
+}
+
+impl<T: Bounded + PartialOrd> Directed<T> {
+    /// Returns the value, or the largest or smallest value of the type if it failed in that
+    /// direction.
+    /// # Panics
+    /// Panics if it failed in no known direction.
+    #[inline]
+    #[track_caller]
+    pub fn unwrap_or_saturate(self) -> T {
+        self.unwrap_or_clamp(T::min_value(), T::max_value())
+    }
+}
+
+impl<T: PartialOrd> Directed<T> {
+    /// Returns the value limited to between `lo` and `hi`, or `hi` or `lo` if it failed in the
+    /// positive or negative direction.
+    /// # Panics
+    /// Panics if it failed in no known direction, or if `lo > hi`.
+    /// # Examples
+    /// ```
+    /// use checked::Directed;
+    ///
+    /// let volume = Directed::new(60_i32);
+    /// assert_eq!(volume.unwrap_or_clamp(0, 100), 60);
+    /// assert_eq!((volume * 2).unwrap_or_clamp(0, 100), 100);
+    /// assert_eq!((volume * i32::MAX).unwrap_or_clamp(0, 100), 100);
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn unwrap_or_clamp(self, lo: T, hi: T) -> T {
+        assert!(lo <= hi, "unwrap_or_clamp called with lo > hi");
+        match self.0 {
+            Ok(x) if x < lo => lo,
+            Ok(x) if x > hi => hi,
+            Ok(x) => x,
+            Err(e) => match e.direction {
+                Direction::Positive => hi,
+                Direction::Negative => lo,
+                Direction::Undefined => panic!("the failed value has no direction to clamp to"),
+            },
+        }
+    }
+}
+
+impl<T> Default for Directed<T> {
+    #[inline]
+    fn default() -> Directed<T> {
+        Directed::OVERFLOW
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Directed<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Ok(ref x) => x.fmt(f),
+            Err(ref e) => fmt_failure(f, e.direction.description(), true),
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Directed<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Ok(ref x) => x.fmt(f),
+            Err(ref e) => fmt_failure(f, e.direction.description(), false),
+        }
+    }
+}
+
+// implements the radix and exponent formatting traits
+macro_rules! impl_directed_fmt {
+    ($($imp:ident)*) => {
+        $(
+            impl<T: fmt::$imp> fmt::$imp for Directed<T> {
+                #[inline]
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    match self.0 {
+                        Ok(ref x) => x.fmt(f),
+                        Err(ref e) => fmt_failure(f, e.direction.description(), false),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_directed_fmt! { LowerHex UpperHex Octal Binary LowerExp UpperExp }
+
+impl<T> From<T> for Directed<T> {
+    #[inline]
+    fn from(x: T) -> Directed<T> {
+        Directed(Ok(x))
+    }
+}
+
+impl<T> From<Checked<T>> for Directed<T> {
+    #[inline]
+    fn from(x: Checked<T>) -> Directed<T> {
+        match x.0 {
+            Some(x) => Directed(Ok(x)),
+            None => Directed::OVERFLOW,
+        }
+    }
+}
+
+impl<T> From<Directed<T>> for Checked<T> {
+    #[inline]
+    fn from(x: Directed<T>) -> Checked<T> {
+        Checked(x.0.ok())
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for Directed<T> {
+    fn partial_cmp(&self, other: &Directed<T>) -> Option<Ordering> {
+        // As for `Checked`, the failed state can't be compared.
+        match (&self.0, &other.0) {
+            (Ok(x), Ok(y)) => PartialOrd::partial_cmp(x, y),
+            _ => None,
+        }
+    }
+}
+
+impl<T> Zero for Directed<T>
+where
+    T: Zero,
+    Directed<T>: Add<Directed<T>, Output = Directed<T>>,
+{
+    fn zero() -> Directed<T> {
+        Directed(Ok(T::zero()))
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.as_ref().is_ok_and(Zero::is_zero)
+    }
+}
+
+impl<T> One for Directed<T>
+where
+    T: One,
+    Directed<T>: Mul<Directed<T>, Output = Directed<T>>,
+{
+    fn one() -> Directed<T> {
+        Directed(Ok(T::one()))
+    }
+}
+
+// What's known about the sign of an operand: a valid value's sign, or a failure's direction.
+#[derive(Clone, Copy, PartialEq)]
+enum Sign {
+    Negative,
+    Zero,
+    Positive,
+    Overflow(Direction),
+}
+
+impl Sign {
+    fn of<T: Copy>(x: &Result<T, Overflow<T>>) -> Sign
+    where
+        Value: From<T>,
+    {
+        match *x {
+            Ok(x) if Value::from(x).is_negative() => Sign::Negative,
+            Ok(x) if Value::from(x).is_zero() => Sign::Zero,
+            Ok(_) => Sign::Positive,
+            Err(ref e) => Sign::Overflow(e.direction),
+        }
+    }
+
+    fn negate(self) -> Sign {
+        match self {
+            Sign::Negative => Sign::Positive,
+            Sign::Positive => Sign::Negative,
+            other => other,
+        }
+    }
+}
+
+// Works out the direction of an operation on operands of which at least one had already failed.
+// It is only `Positive` or `Negative` when that holds however far the failed operands overflowed.
+fn propagate(op: Op, x: Sign, y: Sign, signed: bool) -> Direction {
+    use self::Direction::*;
+
+    match (op, x, y) {
+        (_, Sign::Overflow(Undefined), _) | (_, _, Sign::Overflow(Undefined)) => Undefined,
+        (Op::Add, Sign::Overflow(d), Sign::Overflow(e)) if d == e => d,
+        (Op::Add, Sign::Overflow(d), s) | (Op::Add, s, Sign::Overflow(d)) => match (d, s) {
+            (Positive, Sign::Positive) | (Positive, Sign::Zero) => Positive,
+            (Negative, Sign::Negative) | (Negative, Sign::Zero) => Negative,
+            _ => Undefined,
+        },
+        // Subtracting a failed value is left undefined: negating it can land exactly on the limit.
+        (Op::Sub, Sign::Overflow(d), Sign::Overflow(e)) if d != e => d,
+        (Op::Sub, Sign::Overflow(_), Sign::Overflow(_)) => Undefined,
+        (Op::Sub, Sign::Overflow(_), s) => propagate(Op::Add, x, s.negate(), signed),
+        // Two negative overflows of an unsigned type can have wrapped to anything, so their
+        // product is only positive for signed types.
+        (Op::Mul, Sign::Overflow(Negative), Sign::Overflow(Negative)) if !signed => Undefined,
+        (Op::Mul, Sign::Overflow(d), Sign::Overflow(e)) => {
+            if d == e {
+                Positive
+            } else {
+                Negative
+            }
+        }
+        (Op::Mul, Sign::Overflow(d), s) | (Op::Mul, s, Sign::Overflow(d)) => match (d, s) {
+            (d, Sign::Positive) => d,
+            (Negative, Sign::Negative) => Positive,
+            _ => Undefined,
+        },
+        (Op::Shl, Sign::Overflow(d), _) => d,
+        _ => Undefined,
+    }
+}
+
+// Applies an operation that can fail. A new failure is reported to the hooks, as for `Checked`.
+// `wrapping` gives the wrapped result, which is only carried over from failed operands by the
+// operations that wrap consistently.
+#[inline]
+#[track_caller]
+fn apply<T, U>(
+    op: Op,
+    x: Result<T, Overflow<T>>,
+    y: Result<U, Overflow<U>>,
+    checked: fn(T, U) -> Option<T>,
+    wrapping: fn(T, U) -> Option<T>,
+) -> Directed<T>
+where
+    T: Copy + Bounded + Primitive,
+    U: Copy,
+    Value: From<T> + From<U>,
+{
+    let (a, b) = match (x, y) {
+        (Ok(a), Ok(b)) => (a, b),
+        (x, y) => {
+            let signed = Value::from(T::min_value()).is_negative();
+            let direction = propagate(op, Sign::of(&x), Sign::of(&y), signed);
+            let wrapped = match (op, x, y) {
+                (Op::Add, _, _) | (Op::Sub, _, _) | (Op::Mul, _, _) | (Op::Shl, _, _) => {
+                    match (unwrap_or_wrapped(x), unwrap_or_wrapped(y)) {
+                        (Some(a), Some(b)) => wrapping(a, b),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            };
+            return Directed(Err(Overflow { direction, wrapped }));
+        }
+    };
+    match hook::check(checked(a, b), op, a, Some(b)) {
+        Some(result) => Directed(Ok(result)),
+        None => {
+            let (lhs, rhs) = (Value::from(a), Value::from(b));
+            let direction = match op {
+                // The only remainder that fails by a non-zero value is `MIN % -1`, which is 0.
+                Op::Rem => Direction::Undefined,
+                _ => Direction::from(Reason::of(op, lhs, Some(rhs))),
+            };
+            Directed(Err(Overflow {
+                direction,
+                wrapped: wrapping(a, b),
+            }))
+        }
+    }
+}
+
+#[inline]
+fn unwrap_or_wrapped<T>(x: Result<T, Overflow<T>>) -> Option<T> {
+    match x {
+        Ok(x) => Some(x),
+        Err(e) => e.wrapped,
+    }
+}
+
+// Applies an operation that can't fail, to the wrapped result of a failed value.
+#[inline]
+fn apply_unchecked<T: Copy>(
+    x: Result<T, Overflow<T>>,
+    y: Result<T, Overflow<T>>,
+    f: fn(T, T) -> T,
+) -> Directed<T> {
+    match (x, y) {
+        (Ok(a), Ok(b)) => Directed(Ok(f(a, b))),
+        (x, y) => Directed(Err(Overflow {
+            direction: Direction::Undefined,
+            wrapped: match (unwrap_or_wrapped(x), unwrap_or_wrapped(y)) {
+                (Some(a), Some(b)) => Some(f(a, b)),
+                _ => None,
+            },
+        })),
+    }
+}
+
+// implements binary operators for directed types
+macro_rules! impl_directed_binop {
+    (impl $imp:ident, $method:ident, $op:ident for $t:ident: $checked:expr, $wrapping:expr) => {
+        impl $imp for Directed<$t> {
+            type Output = Directed<$t>;
+
+            #[inline]
+            #[track_caller]
+            fn $method(self, other: Directed<$t>) -> Directed<$t> {
+                apply(Op::$op, self.0, other.0, $checked, $wrapping)
+            }
+        }
+
+        impl $imp<$t> for Directed<$t> {
+            type Output = Directed<$t>;
+
+            #[inline]
+            #[track_caller]
+            fn $method(self, other: $t) -> Directed<$t> {
+                $imp::$method(self, Directed(Ok(other)))
+            }
+        }
+
+        impl $imp<Directed<$t>> for $t {
+            type Output = Directed<$t>;
+
+            #[inline]
+            #[track_caller]
+            fn $method(self, other: Directed<$t>) -> Directed<$t> {
+                $imp::$method(Directed(Ok(self)), other)
+            }
+        }
+
+        forward_ref_binop! { #[track_caller] impl [] $imp, $method for Directed<$t>, Directed<$t> {} }
+        forward_ref_binop! { #[track_caller] impl [] $imp, $method for Directed<$t>, $t {} }
+        forward_ref_binop! { #[track_caller] impl [] $imp, $method for $t, Directed<$t> {} }
+    };
+}
+
+// implements binary operators for directed types (no checked method)
+macro_rules! impl_directed_binop_unchecked {
+    (impl $imp:ident, $method:ident for $t:ident {$sym:tt}) => {
+        impl $imp for Directed<$t> {
+            type Output = Directed<$t>;
+
+            #[inline]
+            fn $method(self, other: Directed<$t>) -> Directed<$t> {
+                apply_unchecked(self.0, other.0, |x, y| x $sym y)
+            }
+        }
+
+        impl $imp<$t> for Directed<$t> {
+            type Output = Directed<$t>;
+
+            #[inline]
+            fn $method(self, other: $t) -> Directed<$t> {
+                $imp::$method(self, Directed(Ok(other)))
+            }
+        }
+
+        impl $imp<Directed<$t>> for $t {
+            type Output = Directed<$t>;
+
+            #[inline]
+            fn $method(self, other: Directed<$t>) -> Directed<$t> {
+                $imp::$method(Directed(Ok(self)), other)
+            }
+        }
+
+        forward_ref_binop! { impl $imp, $method for Directed<$t>, Directed<$t> {} }
+        forward_ref_binop! { impl $imp, $method for Directed<$t>, $t {} }
+        forward_ref_binop! { impl $imp, $method for $t, Directed<$t> {} }
+    };
+}
+
+// implements assignment operators for directed types
+macro_rules! impl_directed_binop_assign {
+    (impl $imp:ident, $method:ident for $t:ident, $u:ty {$sym:tt}) => {
+        impl $imp<$u> for Directed<$t> {
+            #[inline]
+            #[track_caller]
+            fn $method(&mut self, other: $u) {
+                *self = *self $sym other;
+            }
+        }
+    };
+    (impl $imp:ident, $method:ident for $t:ident {$sym:tt}) => {
+        impl_directed_binop_assign! { impl $imp, $method for $t, Directed<$t> {$sym} }
+        impl_directed_binop_assign! { impl $imp, $method for $t, $t {$sym} }
+    };
+}
+
+// implements shifts for directed types, by `u32` as for `Checked`
+macro_rules! impl_directed_sh {
+    (impl $imp:ident, $method:ident, $op:ident, $imp_assign:ident, $method_assign:ident for $t:ident: $checked:expr, $wrapping:expr, {$sym:tt}) => {
+        impl $imp<Directed<u32>> for Directed<$t> {
+            type Output = Directed<$t>;
+
+            #[inline]
+            #[track_caller]
+            fn $method(self, other: Directed<u32>) -> Directed<$t> {
+                apply(Op::$op, self.0, other.0, $checked, $wrapping)
+            }
+        }
+
+        impl $imp<u32> for Directed<$t> {
+            type Output = Directed<$t>;
+
+            #[inline]
+            #[track_caller]
+            fn $method(self, other: u32) -> Directed<$t> {
+                $imp::$method(self, Directed(Ok(other)))
+            }
+        }
+
+        forward_ref_binop! { #[track_caller] impl [] $imp, $method for Directed<$t>, Directed<u32> {} }
+        forward_ref_binop! { #[track_caller] impl [] $imp, $method for Directed<$t>, u32 {} }
+
+        impl_directed_binop_assign! { impl $imp_assign, $method_assign for $t, u32 {$sym} }
+        impl_directed_binop_assign! { impl $imp_assign, $method_assign for $t, Directed<u32> {$sym} }
+    };
+}
+
+macro_rules! directed_impl {
+    ($($t:ident)*) => {
+        $(
+            impl_directed_binop! { impl Add, add, Add for $t: $t::checked_add, |x, y| Some(x.wrapping_add(y)) }
+            impl_directed_binop_assign! { impl AddAssign, add_assign for $t {+} }
+            impl_directed_binop! { impl Sub, sub, Sub for $t: $t::checked_sub, |x, y| Some(x.wrapping_sub(y)) }
+            impl_directed_binop_assign! { impl SubAssign, sub_assign for $t {-} }
+            impl_directed_binop! { impl Mul, mul, Mul for $t: $t::checked_mul, |x, y| Some(x.wrapping_mul(y)) }
+            impl_directed_binop_assign! { impl MulAssign, mul_assign for $t {*} }
+            impl_directed_binop! { impl Div, div, Div for $t: $t::checked_div, |x, y| {
+                if y == 0 { None } else { Some(x.wrapping_div(y)) }
+            } }
+            impl_directed_binop_assign! { impl DivAssign, div_assign for $t {/} }
+            impl_directed_binop! { impl Rem, rem, Rem for $t: $t::checked_rem, |x, y| {
+                if y == 0 { None } else { Some(x.wrapping_rem(y)) }
+            } }
+            impl_directed_binop_assign! { impl RemAssign, rem_assign for $t {%} }
+            impl_directed_binop_unchecked! { impl BitXor, bitxor for $t {^} }
+            impl_directed_binop_assign! { impl BitXorAssign, bitxor_assign for $t {^} }
+            impl_directed_binop_unchecked! { impl BitOr, bitor for $t {|} }
+            impl_directed_binop_assign! { impl BitOrAssign, bitor_assign for $t {|} }
+            impl_directed_binop_unchecked! { impl BitAnd, bitand for $t {&} }
+            impl_directed_binop_assign! { impl BitAndAssign, bitand_assign for $t {&} }
+            impl_directed_sh! { impl Shl, shl, Shl, ShlAssign, shl_assign for $t:
+                $t::checked_shl, |x, y| Some(x.wrapping_shl(y)), {<<} }
+            impl_directed_sh! { impl Shr, shr, Shr, ShrAssign, shr_assign for $t:
+                $t::checked_shr, |x, y| Some(x.wrapping_shr(y)), {>>} }
+
+            impl Neg for Directed<$t> {
+                type Output = Directed<$t>;
+
+                #[inline]
+                #[track_caller]
+                fn neg(self) -> Directed<$t> {
+                    match self.0 {
+                        Ok(x) => match hook::check(x.checked_neg(), Op::Neg, x, None::<$t>) {
+                            Some(result) => Directed(Ok(result)),
+                            None => Directed(Err(Overflow {
+                                direction: Direction::from(Reason::of(Op::Neg, Value::from(x), None)),
+                                wrapped: Some(x.wrapping_neg()),
+                            })),
+                        },
+                        // The negation of a negative overflow is a positive overflow for signed
+                        // types, but for unsigned ones it could be anything.
+                        Err(e) => Directed(Err(Overflow {
+                            direction: Direction::Undefined,
+                            wrapped: e.wrapped.map($t::wrapping_neg),
+                        })),
+                    }
+                }
+            }
+
+            forward_ref_unop! { #[track_caller] impl [] Neg, neg for Directed<$t> {} }
+
+            impl Not for Directed<$t> {
+                type Output = Directed<$t>;
+
+                #[inline]
+                fn not(self) -> Directed<$t> {
+                    match self.0 {
+                        Ok(x) => Directed(Ok(!x)),
+                        Err(e) => Directed(Err(Overflow {
+                            direction: Direction::Undefined,
+                            wrapped: e.wrapped.map(|x| !x),
+                        })),
+                    }
+                }
+            }
+
+            forward_ref_unop! { impl Not, not for Directed<$t> {} }
+        )*
+    };
+}
+
+directed_impl! { u8 u16 u32 u64 usize i8 i16 i32 i64 isize }
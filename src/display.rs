@@ -0,0 +1,296 @@
+use core::any;
+use core::fmt::{self, Write};
+
+use directed::{Directed, Direction};
+use num::{pad_numeric, Checked};
+
+/// Options for [`Checked::display_with`](struct.Checked.html#method.display_with) and
+/// [`Directed::display_with`](struct.Directed.html#method.display_with).
+///
+/// The defaults print the same thing as `Display`.
+/// # Examples
+/// ```
+/// use checked::{Checked, DisplayOptions};
+///
+/// let options = DisplayOptions::new().failure_text("too big").thousands_separator(',');
+/// assert_eq!(Checked::from(1_234_567_u32).display_with(options).to_string(), "1,234,567");
+/// assert_eq!(Checked::<u32>::from(None).display_with(options).to_string(), "too big");
+///
+/// let options = options.show_type(true);
+/// assert_eq!(Checked::<u8>::from(None).display_with(options).to_string(), "Checked<u8>(too big)");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DisplayOptions<'a> {
+    failure_text: &'a str,
+    show_type: bool,
+    separator: Option<char>,
+    show_reason: bool,
+}
+
+impl<'a> DisplayOptions<'a> {
+    /// Creates the default options: no type, no separators, and "overflow" for the failure state.
+    #[inline]
+    pub fn new() -> DisplayOptions<'a> {
+        DisplayOptions {
+            failure_text: "overflow",
+            show_type: false,
+            separator: None,
+            show_reason: false,
+        }
+    }
+
+    /// Sets the text shown in place of a number for the failure state.
+    #[inline]
+    pub fn failure_text(mut self, text: &'a str) -> DisplayOptions<'a> {
+        self.failure_text = text;
+        self
+    }
+
+    /// Wraps the output in the type, as in `Checked<u8>(overflow)`.
+    #[inline]
+    pub fn show_type(mut self, show: bool) -> DisplayOptions<'a> {
+        self.show_type = show;
+        self
+    }
+
+    /// Groups the digits of numbers in threes, separated by `separator`.
+    #[inline]
+    pub fn thousands_separator(mut self, separator: char) -> DisplayOptions<'a> {
+        self.separator = Some(separator);
+        self
+    }
+
+    /// Appends the reason for the failure, as in `overflow (positive overflow)`, for values that
+    /// know why they failed, like a `Directed` with a direction.
+    /// A plain `Checked` doesn't keep track of that, so it only ever shows the failure text.
+    #[inline]
+    pub fn show_reason(mut self, show: bool) -> DisplayOptions<'a> {
+        self.show_reason = show;
+        self
+    }
+}
+
+impl<'a> Default for DisplayOptions<'a> {
+    #[inline]
+    fn default() -> DisplayOptions<'a> {
+        DisplayOptions::new()
+    }
+}
+
+/// Displays a `Checked` or `Directed` value according to some
+/// [`DisplayOptions`](struct.DisplayOptions.html).
+///
+/// The alternate flag (`{:#}`) switches to the verbose form, which shows the type and the reason
+/// for a failure regardless of the options.
+/// Width, fill and alignment apply to the whole output. The sign flag (`{:+}`) and the precision
+/// are passed on to the number, grouped or not, and don't affect the failure text.
+/// # Examples
+/// ```
+/// use checked::{Checked, DisplayOptions};
+///
+/// let x = Checked::<u8>::from(None);
+/// assert_eq!(format!("{:#}", x.display_with(DisplayOptions::new())), "Checked<u8>(overflow)");
+/// assert_eq!(format!("{:>12}", x.display_with(DisplayOptions::new())), "    overflow");
+///
+/// let options = DisplayOptions::new().thousands_separator(',');
+/// assert_eq!(format!("{:+}", Checked::new(1_234_567).display_with(options)), "+1,234,567");
+/// assert_eq!(format!("{:.1}", Checked::new(1_234.56).display_with(options)), "1234.6");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct CheckedDisplay<'a, T: 'a> {
+    wrapper: &'static str,
+    value: Option<&'a T>,
+    reason: Option<&'static str>,
+    options: DisplayOptions<'a>,
+}
+
+impl<'a, T: fmt::Display> CheckedDisplay<'a, T> {
+    // `wrapper` is the name of the type the value came from, for `show_type`.
+    pub(crate) fn new(
+        wrapper: &'static str,
+        value: Option<&'a T>,
+        reason: Option<&'static str>,
+        options: DisplayOptions<'a>,
+    ) -> CheckedDisplay<'a, T> {
+        CheckedDisplay {
+            wrapper,
+            value,
+            reason,
+            options,
+        }
+    }
+
+    // Writes the whole output, unpadded.
+    // `digits` is the number of digits to group, if the number should be grouped.
+    fn render(
+        &self,
+        out: &mut dyn Write,
+        flags: Flags,
+        verbose: bool,
+        digits: Option<usize>,
+    ) -> fmt::Result {
+        let show_type = self.options.show_type || verbose;
+        if show_type {
+            write!(out, "{}<{}>(", self.wrapper, any::type_name::<T>())?;
+        }
+        match (self.value, self.options.separator, digits) {
+            (Some(x), Some(separator), Some(digits)) => flags.write(
+                &mut Grouped {
+                    out,
+                    separator,
+                    left: digits,
+                    started: false,
+                },
+                x,
+            )?,
+            (Some(x), _, _) => flags.write(out, x)?,
+            (None, _, _) => {
+                out.write_str(self.options.failure_text)?;
+                if let (Some(reason), true) = (self.reason, self.options.show_reason || verbose) {
+                    write!(out, " ({})", reason)?;
+                }
+            }
+        }
+        if show_type {
+            out.write_char(')')?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, T: fmt::Display> fmt::Display for CheckedDisplay<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let verbose = f.alternate();
+        let flags = Flags {
+            plus: f.sign_plus(),
+            precision: f.precision(),
+        };
+        // Only numbers that are nothing but (signed) digits get grouped.
+        let digits = match (self.value, self.options.separator) {
+            (Some(x), Some(_)) => {
+                let mut count = Count::default();
+                flags.write(&mut count, x)?;
+                if count.plain {
+                    Some(count.digits)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        };
+        if f.width().is_none() {
+            return self.render(f, flags, verbose, digits);
+        }
+        let mut count = Count::default();
+        self.render(&mut count, flags, verbose, digits)?;
+        pad_numeric(f, count.chars, |f| self.render(f, flags, verbose, digits))
+    }
+}
+
+// The flags of the format string that are passed on to the number.
+#[derive(Clone, Copy)]
+struct Flags {
+    plus: bool,
+    precision: Option<usize>,
+}
+
+impl Flags {
+    fn write<T: fmt::Display>(self, out: &mut dyn Write, x: &T) -> fmt::Result {
+        match (self.plus, self.precision) {
+            (false, None) => write!(out, "{}", x),
+            (true, None) => write!(out, "{:+}", x),
+            (false, Some(precision)) => write!(out, "{:.*}", precision, x),
+            (true, Some(precision)) => write!(out, "{:+.*}", precision, x),
+        }
+    }
+}
+
+impl<T: fmt::Display> Checked<T> {
+    /// Returns an adapter that displays this value according to `options`.
+    /// # Examples
+    /// ```
+    /// use checked::{Checked, DisplayOptions};
+    ///
+    /// let total = Checked::from(1_000_000_u32) * 5;
+    /// let options = DisplayOptions::new().thousands_separator('_');
+    /// println!("total: {}", total.display_with(options));
+    /// assert_eq!(total.display_with(options).to_string(), "5_000_000");
+    /// ```
+    #[inline]
+    pub fn display_with<'a>(&'a self, options: DisplayOptions<'a>) -> CheckedDisplay<'a, T> {
+        CheckedDisplay::new("Checked", self.as_ref(), None, options)
+    }
+}
+
+impl<T: fmt::Display> Directed<T> {
+    /// Returns an adapter that displays this value according to `options`, with the direction
+    /// of a failure as its reason.
+    /// # Examples
+    /// ```
+    /// use checked::{Directed, DisplayOptions};
+    ///
+    /// let level = Directed::new(200_u8) + 100;
+    /// let options = DisplayOptions::new().show_reason(true);
+    /// assert_eq!(level.display_with(options).to_string(), "overflow (positive overflow)");
+    /// assert_eq!(format!("{:#}", (level / 0).display_with(options)), "Directed<u8>(overflow)");
+    /// ```
+    #[inline]
+    pub fn display_with<'a>(&'a self, options: DisplayOptions<'a>) -> CheckedDisplay<'a, T> {
+        let (value, reason) = match self.0 {
+            Ok(ref x) => (Some(x), None),
+            Err(ref e) if e.direction == Direction::Undefined => (None, None),
+            Err(ref e) => (None, Some(e.direction.description())),
+        };
+        CheckedDisplay::new("Directed", value, reason, options)
+    }
+}
+
+// Counts what gets written, and whether it's a plain (optionally signed) number.
+#[derive(Default)]
+struct Count {
+    chars: usize,
+    digits: usize,
+    plain: bool,
+}
+
+impl Write for Count {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            if self.chars == 0 {
+                self.plain = true;
+            }
+            if c.is_ascii_digit() {
+                self.digits += 1;
+            } else if self.chars != 0 || (c != '-' && c != '+') {
+                self.plain = false;
+            }
+            self.chars += 1;
+        }
+        Ok(())
+    }
+}
+
+// Inserts a separator between every group of three digits.
+struct Grouped<'a, 'b: 'a> {
+    out: &'a mut (dyn Write + 'b),
+    separator: char,
+    left: usize,
+    started: bool,
+}
+
+impl<'a, 'b> Write for Grouped<'a, 'b> {
+    #[allow(clippy::manual_is_multiple_of)] // `is_multiple_of` is too new
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            if c.is_ascii_digit() {
+                if self.started && self.left % 3 == 0 {
+                    self.out.write_char(self.separator)?;
+                }
+                self.started = true;
+                self.left -= 1;
+            }
+            self.out.write_char(c)?;
+        }
+        Ok(())
+    }
+}
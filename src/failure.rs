@@ -0,0 +1,305 @@
+use core::any;
+use core::fmt;
+
+/// An arithmetic operation that can fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op {
+    /// `+`
+    Add,
+    /// `-`
+    Sub,
+    /// `*`
+    Mul,
+    /// `/`
+    Div,
+    /// `%`
+    Rem,
+    /// `<<`
+    Shl,
+    /// `>>`
+    Shr,
+    /// unary `-`
+    Neg,
+}
+
+impl Op {
+    /// All the operations.
+    pub const ALL: [Op; 8] = [
+        Op::Add,
+        Op::Sub,
+        Op::Mul,
+        Op::Div,
+        Op::Rem,
+        Op::Shl,
+        Op::Shr,
+        Op::Neg,
+    ];
+
+    /// The operator's symbol.
+    /// # Examples
+    /// ```
+    /// use checked::Op;
+    ///
+    /// assert_eq!(Op::Shl.symbol(), "<<");
+    /// ```
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Op::Add => "+",
+            Op::Sub | Op::Neg => "-",
+            Op::Mul => "*",
+            Op::Div => "/",
+            Op::Rem => "%",
+            Op::Shl => "<<",
+            Op::Shr => ">>",
+        }
+    }
+}
+
+impl fmt::Display for Op {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+/// Why an operation failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Reason {
+    /// The result was larger than the largest value of the type.
+    PositiveOverflow,
+    /// The result was smaller than the smallest value of the type.
+    NegativeOverflow,
+    /// Division or remainder by zero.
+    DivisionByZero,
+    /// A shift by at least the number of bits in the type.
+    ShiftOverflow,
+}
+
+impl Reason {
+    /// Works out why `op` failed on the operands `x` and `y` (`None` for negation).
+    ///
+    /// This assumes that it did fail: the operands alone don't say whether it would.
+    /// # Examples
+    /// ```
+    /// use checked::{Op, Reason, Value};
+    ///
+    /// let (x, y) = (Value::from(-100_i8), Value::from(2_i8));
+    /// assert_eq!(Reason::of(Op::Mul, x, Some(y)), Reason::NegativeOverflow);
+    /// assert_eq!(Reason::of(Op::Div, x, Some(Value::from(0_i8))), Reason::DivisionByZero);
+    /// ```
+    pub fn of(op: Op, x: Value, y: Option<Value>) -> Reason {
+        let y = match (op, y) {
+            (Op::Shl, _) | (Op::Shr, _) => return Reason::ShiftOverflow,
+            // Only the most negative value overflows when negated, and unsigned values go below 0.
+            (Op::Neg, _) | (_, None) => {
+                return if x.is_negative() {
+                    Reason::PositiveOverflow
+                } else {
+                    Reason::NegativeOverflow
+                }
+            }
+            (_, Some(y)) => y,
+        };
+        let negative = match op {
+            Op::Add => y.is_negative(),
+            Op::Sub => !y.is_negative(),
+            Op::Mul => x.is_negative() != y.is_negative(),
+            // Otherwise it's the most negative value divided by -1.
+            _ if y.is_zero() => return Reason::DivisionByZero,
+            _ => false,
+        };
+        if negative {
+            Reason::NegativeOverflow
+        } else {
+            Reason::PositiveOverflow
+        }
+    }
+
+    /// A short description, such as "division by zero".
+    pub fn description(self) -> &'static str {
+        match self {
+            Reason::PositiveOverflow => "positive overflow",
+            Reason::NegativeOverflow => "negative overflow",
+            Reason::DivisionByZero => "division by zero",
+            Reason::ShiftOverflow => "shift amount too large",
+        }
+    }
+}
+
+impl fmt::Display for Reason {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+/// The value of an operand, of any of the primitive integer types.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Value {
+    /// A value of a signed type.
+    Signed(i128),
+    /// A value of an unsigned type.
+    Unsigned(u128),
+}
+
+impl Value {
+    /// Whether the value is less than zero.
+    #[inline]
+    pub fn is_negative(self) -> bool {
+        match self {
+            Value::Signed(x) => x < 0,
+            Value::Unsigned(_) => false,
+        }
+    }
+
+    /// Whether the value is zero.
+    #[inline]
+    pub fn is_zero(self) -> bool {
+        match self {
+            Value::Signed(x) => x == 0,
+            Value::Unsigned(x) => x == 0,
+        }
+    }
+}
+
+impl fmt::Debug for Value {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl fmt::Display for Value {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Value::Signed(ref x) => x.fmt(f),
+            Value::Unsigned(ref x) => x.fmt(f),
+        }
+    }
+}
+
+macro_rules! impl_value {
+    ($variant:ident, $wide:ident, $($t:ident)*) => {
+        $(
+            impl From<$t> for Value {
+                #[inline]
+                fn from(x: $t) -> Value {
+                    Value::$variant(x as $wide)
+                }
+            }
+        )*
+    };
+}
+
+impl_value! { Signed, i128, i8 i16 i32 i64 isize i128 }
+impl_value! { Unsigned, u128, u8 u16 u32 u64 usize u128 }
+
+/// The error from [`checked_expr!`](macro.checked_expr.html): which part of the expression failed,
+/// on which values, and why.
+/// # Examples
+/// ```
+/// use checked::{ExprError, Op, Reason, Value};
+///
+/// let error = ExprError {
+///     expr: "count * entry_size",
+///     op: Op::Mul,
+///     lhs: Value::from(3_000_000_u32),
+///     rhs: Some(Value::from(4096_u32)),
+///     reason: Reason::PositiveOverflow,
+/// };
+/// assert_eq!(
+///     error.to_string(),
+///     "positive overflow in `count * entry_size`: 3000000 * 4096",
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExprError {
+    /// The sub-expression that failed.
+    pub expr: &'static str,
+    /// Its operation.
+    pub op: Op,
+    /// The left operand, or the only one for negation.
+    pub lhs: Value,
+    /// The right operand (`None` for negation).
+    pub rhs: Option<Value>,
+    /// Why it failed.
+    pub reason: Reason,
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let operation = Operation {
+            op: self.op,
+            lhs: self.lhs,
+            rhs: self.rhs,
+        };
+        write!(f, "{} in `{}`: {}", self.reason, self.expr, operation)
+    }
+}
+
+// Displays an operation on its operands, as in `200 + 100` or `-(-128)`.
+pub(crate) struct Operation {
+    pub op: Op,
+    pub lhs: Value,
+    pub rhs: Option<Value>,
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.rhs {
+            Some(rhs) => write!(f, "{} {} {}", self.lhs, self.op, rhs),
+            None if self.lhs.is_negative() => write!(f, "{}({})", self.op, self.lhs),
+            None => write!(f, "{}{}", self.op, self.lhs),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for ExprError {}
+
+/// The error for a failed `Checked` value, from [`into_result`](struct.Checked.html#method.into_result)
+/// or `TryFrom`.
+/// # Examples
+/// ```
+/// use checked::{Checked, OverflowError};
+///
+/// fn frame_size(width: u32, height: u32) -> Result<u32, OverflowError> {
+///     let size = Checked::new(width) * height * 4;
+///     size.into_result()
+/// }
+///
+/// assert_eq!(frame_size(640, 480), Ok(1_228_800));
+/// assert_eq!(frame_size(100_000, 100_000).unwrap_err().to_string(), "u32 arithmetic failed");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OverflowError {
+    /// The name of the type, such as `u32`.
+    pub type_name: &'static str,
+    /// Why it failed, if that's known.
+    pub reason: Option<Reason>,
+}
+
+impl OverflowError {
+    /// An error for the type `T`, for an unknown reason.
+    #[inline]
+    pub fn new<T>() -> OverflowError {
+        OverflowError {
+            type_name: any::type_name::<T>(),
+            reason: None,
+        }
+    }
+}
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} arithmetic failed", self.type_name)?;
+        match self.reason {
+            Some(reason) => write!(f, " ({})", reason),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for OverflowError {}
@@ -0,0 +1,243 @@
+//! Hooks called whenever a `Checked` operation fails.
+//!
+//! The operators of `Checked`, `Arith` and `Directed` on the primitive types call the hooks when
+//! an operation on valid operands fails, so a service can log or count its overflows without
+//! changing any of the code that does the arithmetic. Operations on a value that had already
+//! failed don't call them again.
+//!
+//! There is one process-wide hook, a plain function that works without `std`, and with the
+//! `std` feature, a hook for each thread, which can be any closure. When both are set, the
+//! thread's hook is called first.
+//!
+//! Operations that succeed never look at the hooks. When one fails and no hook is set, checking
+//! for them costs a single atomic load.
+//! # Examples
+//! ```
+//! use checked::{hook, Checked, Op};
+//! use std::cell::Cell;
+//! use std::rc::Rc;
+//!
+//! # #[cfg(feature = "std")]
+//! # fn main() {
+//! let failures = Rc::new(Cell::new(0));
+//! let counter = failures.clone();
+//! hook::set_local(move |event: &hook::Event| {
+//!     assert_eq!(event.op, Op::Mul);
+//!     assert_eq!(event.type_name, "u8");
+//!     counter.set(counter.get() + 1);
+//! });
+//!
+//! let x = Checked::new(16_u8) * 16;
+//! let _ = x + 1; // already failed, so not reported again
+//! hook::clear_local();
+//! assert_eq!(failures.get(), 1);
+//! # }
+//! # #[cfg(not(feature = "std"))]
+//! # fn main() {}
+//! ```
+
+use core::fmt;
+use core::mem;
+use core::panic::Location;
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, Ordering};
+#[cfg(feature = "std")]
+use core::sync::atomic::AtomicUsize;
+#[cfg(feature = "std")]
+use std::boxed::Box;
+#[cfg(feature = "std")]
+use std::cell::RefCell;
+
+use failure::{Op, Operation, Reason, Value};
+#[cfg(feature = "stats")]
+use stats;
+
+/// A failed operation, as given to the hooks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Event {
+    /// The operation.
+    pub op: Op,
+    /// The name of the type, such as `u8`.
+    pub type_name: &'static str,
+    /// The left operand, or the only one for negation.
+    pub lhs: Value,
+    /// The right operand (`None` for negation).
+    pub rhs: Option<Value>,
+    /// Why it failed.
+    pub reason: Reason,
+    /// Where the operator was used, if that's known.
+    pub location: Option<&'static Location<'static>>,
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let operation = Operation {
+            op: self.op,
+            lhs: self.lhs,
+            rhs: self.rhs,
+        };
+        write!(
+            f,
+            "{} arithmetic failed: {} ({})",
+            self.type_name, operation, self.reason
+        )?;
+        match self.location {
+            Some(location) => write!(f, " at {}", location),
+            None => Ok(()),
+        }
+    }
+}
+
+// The process-wide hook, as a `fn(&Event)`, or null.
+static GLOBAL: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+// How many threads have a hook, so that the others can skip looking.
+#[cfg(feature = "std")]
+static LOCAL_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(feature = "std")]
+type LocalHook = Box<dyn Fn(&Event)>;
+
+// A thread's hook, which is taken out of the count when the thread exits.
+#[cfg(feature = "std")]
+struct LocalSlot(RefCell<Option<LocalHook>>);
+
+#[cfg(feature = "std")]
+impl Drop for LocalSlot {
+    fn drop(&mut self) {
+        if self.0.get_mut().is_some() {
+            LOCAL_COUNT.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    static LOCAL: LocalSlot = LocalSlot(RefCell::new(None));
+}
+
+/// Sets the process-wide hook, replacing any previous one.
+///
+/// The hook must not do checked arithmetic that fails, or it would be called for that too.
+pub fn set(hook: fn(&Event)) {
+    GLOBAL.store(hook as *mut (), Ordering::Release);
+}
+
+/// Removes the process-wide hook.
+pub fn clear() {
+    GLOBAL.store(ptr::null_mut(), Ordering::Release);
+}
+
+/// Sets the hook for the current thread, replacing any previous one.
+///
+/// The hook must not do checked arithmetic that fails, since it isn't called again for that.
+#[cfg(feature = "std")]
+pub fn set_local<F>(hook: F)
+where
+    F: Fn(&Event) + 'static,
+{
+    LOCAL.with(|local| {
+        if local.0.borrow_mut().replace(Box::new(hook)).is_none() {
+            LOCAL_COUNT.fetch_add(1, Ordering::Relaxed);
+        }
+    });
+}
+
+/// Removes the hook for the current thread.
+#[cfg(feature = "std")]
+pub fn clear_local() {
+    LOCAL.with(|local| {
+        if local.0.borrow_mut().take().is_some() {
+            LOCAL_COUNT.fetch_sub(1, Ordering::Relaxed);
+        }
+    });
+}
+
+// The primitive types that `Checked` has operators for, numbered for the statistics.
+pub(crate) trait Primitive {
+    #[cfg(feature = "stats")]
+    const INDEX: usize;
+    const NAME: &'static str;
+}
+
+macro_rules! impl_primitive {
+    ($($t:ident $i:expr,)*) => {
+        $(
+            impl Primitive for $t {
+                #[cfg(feature = "stats")]
+                const INDEX: usize = $i;
+                const NAME: &'static str = stringify!($t);
+            }
+        )*
+
+        #[cfg(feature = "stats")]
+        pub(crate) const PRIMITIVES: [&str; 10] = [$(stringify!($t)),*];
+    };
+}
+
+impl_primitive! { u8 0, u16 1, u32 2, u64 3, usize 4, i8 5, i16 6, i32 7, i64 8, isize 9, }
+
+// Passes on the result of an operation on valid operands, reporting it to the hooks if it failed.
+#[inline]
+#[track_caller]
+pub(crate) fn check<T, L, R>(result: Option<T>, op: Op, x: L, y: Option<R>) -> Option<T>
+where
+    T: Primitive,
+    Value: From<L> + From<R>,
+{
+    #[cfg(feature = "stats")]
+    stats::record(op, T::INDEX, result.is_none());
+    if result.is_none() {
+        let lhs = Value::from(x);
+        let rhs = y.map(Value::from);
+        failed(op, T::NAME, lhs, rhs, Location::caller());
+    }
+    result
+}
+
+#[cold]
+#[inline(never)]
+fn failed(
+    op: Op,
+    type_name: &'static str,
+    lhs: Value,
+    rhs: Option<Value>,
+    location: &'static Location<'static>,
+) {
+    let global = GLOBAL.load(Ordering::Acquire);
+    #[cfg(feature = "std")]
+    let local = LOCAL_COUNT.load(Ordering::Relaxed) != 0;
+    #[cfg(not(feature = "std"))]
+    let local = false;
+    if global.is_null() && !local {
+        return;
+    }
+
+    let event = Event {
+        op,
+        type_name,
+        lhs,
+        rhs,
+        reason: Reason::of(op, lhs, rhs),
+        location: Some(location),
+    };
+    #[cfg(feature = "std")]
+    {
+        if local {
+            // `try_with` and `try_borrow_mut` skip the hook during thread teardown, or when the
+            // hook itself fails an operation.
+            let _ = LOCAL.try_with(|local| {
+                if let Ok(hook) = local.0.try_borrow_mut() {
+                    if let Some(ref hook) = *hook {
+                        hook(&event);
+                    }
+                }
+            });
+        }
+    }
+    if !global.is_null() {
+        // Only `set` stores anything other than null, and it stores a `fn(&Event)`.
+        let hook = unsafe { mem::transmute::<*mut (), fn(&Event)>(global) };
+        hook(&event);
+    }
+}
@@ -60,10 +60,163 @@
 //! assert!(!(Checked::from(1_000_u32) <= Checked::from(None)));
 //! assert!(!(Checked::from(None) <= Checked::from(1_000_u32)));
 //! ```
+//!
+//! In a long formula it's easy to miss the spot where the first `Checked` needs to go.
+//! The `checked!` macro (with the `macros` feature) converts every operand, so all of the
+//! arithmetic is checked.
+//!
+//! ```
+//! #[macro_use]
+//! extern crate checked;
+//! use checked::Checked;
+//!
+//! # #[cfg(feature = "macros")]
+//! # fn main() {
+//! let (width, height, depth) = (4_000_u32, 3_000_u32, 4_u32);
+//! assert_eq!(checked!(width * height * depth), Checked::from(48_000_000));
+//! assert_eq!(checked!(width * height * depth * 100), Checked::from(None));
+//! # }
+//! # #[cfg(not(feature = "macros"))]
+//! # fn main() {}
+//! ```
+//!
+//! The operators can't be used in constant expressions, but each of them has a `const fn`
+//! counterpart, named after the operator's method with `const_` in front.
+//!
+//! ```
+//! use checked::Checked;
+//!
+//! const TABLE_SIZE: usize = Checked::<usize>::new(4096).const_mul(Checked::new(16)).unwrap_const();
+//! assert_eq!(TABLE_SIZE, 65_536);
+//! ```
+//!
+//! The crate is `no_std`, so all of the above works on bare-metal targets too.
+//! The `std` feature (on by default) adds the parts that need the standard library.
+//!
+//! All the formatting traits of the underlying type are available. Width, fill and alignment apply
+//! to the overflow state as well, so columns stay lined up.
+//!
+//! ```
+//! use checked::Checked;
+//!
+//! assert_eq!(format!("{:#06x}", Checked::from(255_u8)), "0x00ff");
+//! assert_eq!(format!("{:>10x}", Checked::<u8>::from(None)), "  overflow");
+//! assert_eq!(format!("{:e}", Checked::from(1_500_u32)), "1.5e3");
+//! ```
+//!
+//! For output meant for people, [`display_with`](struct.Checked.html#method.display_with) can
+//! change the failure text, show the type and group the digits.
+//!
+//! ```
+//! use checked::{Checked, DisplayOptions};
+//!
+//! let options = DisplayOptions::new().thousands_separator(',').failure_text("too large");
+//! assert_eq!(Checked::from(12_345_678_u32).display_with(options).to_string(), "12,345,678");
+//! assert_eq!(format!("{:#}", Checked::<u8>::from(None).display_with(options)), "Checked<u8>(too large)");
+//! ```
+#![no_std]
+
+#[cfg(any(feature = "std", test))]
+#[cfg_attr(test, macro_use)]
+extern crate std;
+
+#[cfg(feature = "macros")]
+extern crate checked_macros;
 extern crate num_traits;
+#[cfg(feature = "serde")]
+extern crate serde as serde_crate;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_test;
+
+#[macro_use]
+mod macros;
 
+#[macro_use]
 mod num;
 pub use num::Checked;
 
+pub mod ops;
+
+mod failure;
+pub mod hook;
+#[cfg(feature = "stats")]
+pub mod stats;
+pub use failure::{ExprError, Op, OverflowError, Reason, Value};
+
+mod strict;
+pub use strict::Strict;
+
+pub mod policy;
+
+mod arith;
+pub use arith::Arith;
+
+mod acc;
+pub use acc::CheckedAcc;
+
+pub mod accumulator;
+pub use accumulator::Accumulator;
+
+pub mod slice;
+
+mod array;
+pub use array::CheckedArray;
+
+#[cfg(target_has_atomic = "ptr")]
+pub mod atomic;
+#[cfg(target_has_atomic = "ptr")]
+pub use atomic::AtomicChecked;
+
+pub mod compact;
+pub use compact::CompactChecked;
+
+mod directed;
+pub use directed::{Directed, Direction, Overflow};
+
+#[cfg(feature = "std")]
+mod context;
+#[cfg(feature = "std")]
+pub use context::WithContext;
+
+#[cfg(feature = "std")]
+mod trace;
+#[cfg(feature = "std")]
+pub use trace::{Log, Step, Traced};
+
+mod display;
+pub use display::{CheckedDisplay, DisplayOptions};
+
+#[cfg(feature = "serde")]
+pub mod serde;
+
+#[cfg(feature = "macros")]
+pub use checked_macros::{checked_expr, const_checked, CheckedFields, CheckedOps};
+
+/// Attribute macros, enabled by the `macros` feature.
+#[cfg(feature = "macros")]
+pub mod attr {
+    pub use checked_macros::checked;
+}
+
+// Used by the macros.
+#[doc(hidden)]
+pub mod __private {
+    pub use core::clone::Clone;
+    pub use core::cmp::{Eq, PartialEq};
+    pub use core::convert::From;
+    pub use core::default::Default;
+    pub use core::fmt;
+    pub use core::marker::Copy;
+    pub use core::ops;
+    pub use core::option::Option::{self, None, Some};
+    pub use core::result::Result::{Err, Ok};
+    pub use macros::{arith, lift, IntoChecked};
+
+    #[cfg(feature = "macros")]
+    pub use checked_macros::checked_block;
+}
+
 #[cfg(test)]
 mod tests;
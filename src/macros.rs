@@ -0,0 +1,306 @@
+use num::Checked;
+
+/// Evaluates an expression or block with every operator going through `Checked`.
+///
+/// Every operand (variable, field, function or method call) is converted into a `Checked` before
+/// any operator sees it, so there's no way for a step in the middle to use the unchecked primitive
+/// operators by accident.
+/// The result is always a `Checked`.
+///
+/// Literals that are operands are left alone so their type can still be inferred from the other
+/// operand. Arithmetic on nothing but literals is done by the compiler, which rejects it if it
+/// overflows.
+///
+/// Operands are plain integers (or `Checked` values). As with `Checked`, shift amounts are `u32`.
+/// Each operand is converted as a whole, so the arguments of a call and the index in `v[i]` are
+/// evaluated as they are.
+/// Besides the operators and parentheses, blocks may contain `let` bindings and assignments;
+/// the variables bound inside the macro hold `Checked` values, even when they start out as a
+/// literal, and so do the values assigned to them.
+/// Other syntax (`if`, `match`, closures, references) isn't understood; use it outside the macro.
+///
+/// This needs the `macros` feature.
+/// # Examples
+/// ```
+/// #[macro_use]
+/// extern crate checked;
+/// use checked::Checked;
+///
+/// # fn main() {
+/// let (a, b, c, d) = (1_000_000_u32, 5_000_u32, 5_u32, 0_u32);
+/// assert_eq!(checked!(a + c), Checked::from(1_000_005));
+/// assert_eq!(checked!(a * b + c), Checked::from(None));
+/// assert_eq!(checked!(c / d), Checked::from(None));
+/// assert_eq!(checked!(-(c as i64) << 2), Checked::from(-20));
+/// assert_eq!(checked!(a * (1 << 13)), Checked::from(None));
+///
+/// let sizes = [4_u16, 8, 16];
+/// let total = checked! {
+///     let header = 12;
+///     let body = sizes[2] * sizes.len() as u16;
+///     header + body * 2
+/// };
+/// assert_eq!(total, Checked::from(108));
+///
+/// let x = 200_u8;
+/// let total = checked! {
+///     let mut n = 200;
+///     n += 100;
+///     n + x
+/// };
+/// assert_eq!(total, Checked::from(None));
+/// # }
+/// ```
+#[cfg(feature = "macros")]
+#[macro_export]
+macro_rules! checked {
+    ($($tokens:tt)*) => {
+        $crate::__private::checked_block!($($tokens)*)
+    };
+}
+
+/// Evaluates an expression as it is, inside a function with the `#[checked]` attribute.
+///
+/// The attribute doesn't look into macro invocations, so this is how to keep it from rewriting
+/// operators on types other than the integers, such as `Duration`, `Wrapping` or a type of your
+/// own, which it can't tell apart.
+/// # Examples
+/// ```
+/// #[macro_use]
+/// extern crate checked;
+/// use checked::attr::checked;
+/// use std::num::Wrapping;
+///
+/// #[checked(option)]
+/// fn step(count: u8, hash: Wrapping<u8>) -> Option<(u8, Wrapping<u8>)> {
+///     Some((count + 1, unchecked!(hash * Wrapping(31) + Wrapping(count))))
+/// }
+///
+/// # fn main() {
+/// assert_eq!(step(9, Wrapping(100)), Some((10, Wrapping(37))));
+/// assert_eq!(step(255, Wrapping(0)), None);
+/// # }
+/// ```
+#[cfg(feature = "macros")]
+#[macro_export]
+macro_rules! unchecked {
+    ($e:expr) => {
+        $e
+    };
+}
+
+// Converts a plain integer into a `Checked`, and leaves a `Checked` as it is.
+// The integer type is a parameter rather than an associated type so that it can be inferred from
+// the other operand, as in `checked!(a + b)` where `b` is a `u64` and `a` is an unsuffixed literal.
+pub trait IntoChecked<T> {
+    fn into_checked(self) -> Checked<T>;
+}
+
+impl<T> IntoChecked<T> for Checked<T> {
+    #[inline]
+    fn into_checked(self) -> Checked<T> {
+        self
+    }
+}
+
+macro_rules! impl_into_checked {
+    ($($t:ident)*) => {
+        $(
+            impl IntoChecked<$t> for $t {
+                #[inline]
+                fn into_checked(self) -> Checked<$t> {
+                    Checked::new(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_into_checked! { u8 u16 u32 u64 usize i8 i16 i32 i64 isize }
+
+// What `checked!` wraps its operands in.
+#[inline]
+pub fn lift<T, U: IntoChecked<T>>(x: U) -> Checked<T> {
+    x.into_checked()
+}
+
+// What the `#[checked]` attribute turns the operators into.
+// Floats don't overflow, so they're implemented too: the attribute can't tell types apart.
+pub trait Arith: Sized {
+    fn add(self, other: Self) -> Option<Self>;
+    fn sub(self, other: Self) -> Option<Self>;
+    fn mul(self, other: Self) -> Option<Self>;
+    fn div(self, other: Self) -> Option<Self>;
+    fn rem(self, other: Self) -> Option<Self>;
+    fn neg(self) -> Option<Self>;
+}
+
+// Shifts are only for integers, and the amount is a `u32` as with `Checked`.
+pub trait Shift: Sized {
+    fn shl(self, other: u32) -> Option<Self>;
+    fn shr(self, other: u32) -> Option<Self>;
+}
+
+// An operand, which like for the primitive operators may also be a reference.
+// As with `IntoChecked`, the type is a parameter so that literals get inferred from the other side.
+pub trait Operand<T> {
+    fn value(self) -> T;
+}
+
+impl<T: Copy> Operand<T> for &T {
+    #[inline]
+    fn value(self) -> T {
+        *self
+    }
+}
+
+macro_rules! impl_arith {
+    ($($t:ident)*) => {
+        $(
+            impl Operand<$t> for $t {
+                #[inline]
+                fn value(self) -> $t {
+                    self
+                }
+            }
+
+            impl Arith for $t {
+                #[inline]
+                fn add(self, other: $t) -> Option<$t> {
+                    *(Checked::new(self) + other)
+                }
+
+                #[inline]
+                fn sub(self, other: $t) -> Option<$t> {
+                    *(Checked::new(self) - other)
+                }
+
+                #[inline]
+                fn mul(self, other: $t) -> Option<$t> {
+                    *(Checked::new(self) * other)
+                }
+
+                #[inline]
+                fn div(self, other: $t) -> Option<$t> {
+                    *(Checked::new(self) / other)
+                }
+
+                #[inline]
+                fn rem(self, other: $t) -> Option<$t> {
+                    *(Checked::new(self) % other)
+                }
+
+                #[inline]
+                fn neg(self) -> Option<$t> {
+                    *(-Checked::new(self))
+                }
+            }
+
+            impl Shift for $t {
+                #[inline]
+                fn shl(self, other: u32) -> Option<$t> {
+                    *(Checked::new(self) << other)
+                }
+
+                #[inline]
+                fn shr(self, other: u32) -> Option<$t> {
+                    *(Checked::new(self) >> other)
+                }
+            }
+        )*
+    };
+}
+
+impl_arith! { u8 u16 u32 u64 usize i8 i16 i32 i64 isize }
+
+macro_rules! impl_arith_float {
+    ($($t:ident)*) => {
+        $(
+            impl Operand<$t> for $t {
+                #[inline]
+                fn value(self) -> $t {
+                    self
+                }
+            }
+
+            impl Arith for $t {
+                #[inline]
+                fn add(self, other: $t) -> Option<$t> {
+                    Some(self + other)
+                }
+
+                #[inline]
+                fn sub(self, other: $t) -> Option<$t> {
+                    Some(self - other)
+                }
+
+                #[inline]
+                fn mul(self, other: $t) -> Option<$t> {
+                    Some(self * other)
+                }
+
+                #[inline]
+                fn div(self, other: $t) -> Option<$t> {
+                    Some(self / other)
+                }
+
+                #[inline]
+                fn rem(self, other: $t) -> Option<$t> {
+                    Some(self % other)
+                }
+
+                #[inline]
+                fn neg(self) -> Option<$t> {
+                    Some(-self)
+                }
+            }
+        )*
+    };
+}
+
+impl_arith_float! { f32 f64 }
+
+// The functions the operators turn into.
+pub mod arith {
+    use super::{Arith, Operand, Shift};
+
+    #[inline]
+    pub fn add<T: Arith, L: Operand<T>, R: Operand<T>>(x: L, y: R) -> Option<T> {
+        x.value().add(y.value())
+    }
+
+    #[inline]
+    pub fn sub<T: Arith, L: Operand<T>, R: Operand<T>>(x: L, y: R) -> Option<T> {
+        x.value().sub(y.value())
+    }
+
+    #[inline]
+    pub fn mul<T: Arith, L: Operand<T>, R: Operand<T>>(x: L, y: R) -> Option<T> {
+        x.value().mul(y.value())
+    }
+
+    #[inline]
+    pub fn div<T: Arith, L: Operand<T>, R: Operand<T>>(x: L, y: R) -> Option<T> {
+        x.value().div(y.value())
+    }
+
+    #[inline]
+    pub fn rem<T: Arith, L: Operand<T>, R: Operand<T>>(x: L, y: R) -> Option<T> {
+        x.value().rem(y.value())
+    }
+
+    #[inline]
+    pub fn neg<T: Arith, X: Operand<T>>(x: X) -> Option<T> {
+        x.value().neg()
+    }
+
+    #[inline]
+    pub fn shl<T: Shift, L: Operand<T>, R: Operand<u32>>(x: L, y: R) -> Option<T> {
+        x.value().shl(y.value())
+    }
+
+    #[inline]
+    pub fn shr<T: Shift, L: Operand<T>, R: Operand<u32>>(x: L, y: R) -> Option<T> {
+        x.value().shr(y.value())
+    }
+}
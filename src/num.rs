@@ -1,7 +1,11 @@
 use num_traits::{One, Zero};
-use std::fmt;
-use std::ops::*;
-use std::cmp::Ordering;
+use core::fmt::{self, Write};
+use core::ops::*;
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+
+use failure::{Op, OverflowError};
+use policy;
 
 /// The Checked type. See the [module level documentation for more.](index.html)
 #[derive(PartialEq, Eq, Clone, Copy, Hash)]
@@ -19,9 +23,54 @@ impl<T> Checked<T> {
     /// assert_eq!(x * x, y);
     /// ```
     #[inline]
-    pub fn new(x: T) -> Checked<T> {
+    pub const fn new(x: T) -> Checked<T> {
         Checked(Some(x))
     }
+
+    /// The overflow state.
+    /// # Examples
+    /// ```
+    /// use checked::Checked;
+    ///
+    /// const NOTHING: Checked<u32> = Checked::OVERFLOW;
+    /// assert_eq!(NOTHING, Checked::from(None));
+    /// ```
+    pub const OVERFLOW: Checked<T> = Checked(None);
+
+    /// Converts to a `Result`, so that `?` can pass on the failure as an error.
+    /// # Examples
+    /// ```
+    /// use checked::{Checked, OverflowError};
+    ///
+    /// fn total(prices: &[u16]) -> Result<u16, OverflowError> {
+    ///     let mut sum = Checked::new(0_u16);
+    ///     for &price in prices {
+    ///         sum += price;
+    ///     }
+    ///     let sum = sum.into_result()?;
+    ///     Ok(sum)
+    /// }
+    ///
+    /// assert_eq!(total(&[10, 20]), Ok(30));
+    /// assert_eq!(total(&[60_000, 6_000]).unwrap_err().type_name, "u16");
+    /// ```
+    #[inline]
+    pub fn into_result(self) -> Result<T, OverflowError> {
+        match self.0 {
+            Some(x) => Ok(x),
+            None => Err(OverflowError::new::<T>()),
+        }
+    }
+
+    /// Returns a copy of the value, or the error if it failed.
+    /// This is [`into_result`](#method.into_result) without consuming the `Checked`.
+    #[inline]
+    pub fn try_get(&self) -> Result<T, OverflowError>
+    where
+        T: Copy,
+    {
+        self.into_result()
+    }
 }
 
 // The derived Default only works if T has Default
@@ -39,7 +88,7 @@ impl<T: fmt::Debug> fmt::Debug for Checked<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match **self {
             Some(ref x) => x.fmt(f),
-            None => "overflow".fmt(f),
+            None => fmt_overflow(f, true),
         }
     }
 }
@@ -49,11 +98,73 @@ impl<T: fmt::Display> fmt::Display for Checked<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match **self {
             Some(ref x) => x.fmt(f),
-            None => "overflow".fmt(f),
+            None => fmt_overflow(f, false),
         }
     }
 }
 
+// Pads whatever `write` produces (which must be `len` characters long) the way numbers are padded:
+// right-aligned unless the format string says otherwise.
+// Width, fill and alignment apply, so the failure state lines up with numbers in a table.
+// The sign, `#` and `0` flags and the precision only mean something for numbers and are ignored.
+pub(crate) fn pad_numeric<F>(f: &mut fmt::Formatter, len: usize, write: F) -> fmt::Result
+where
+    F: FnOnce(&mut fmt::Formatter) -> fmt::Result,
+{
+    let padding = match f.width() {
+        Some(width) if width > len => width - len,
+        _ => return write(f),
+    };
+    let (pre, post) = match f.align() {
+        Some(fmt::Alignment::Left) => (0, padding),
+        Some(fmt::Alignment::Center) => (padding / 2, padding - padding / 2),
+        _ => (padding, 0),
+    };
+    let fill = f.fill();
+    for _ in 0..pre {
+        f.write_char(fill)?;
+    }
+    write(f)?;
+    for _ in 0..post {
+        f.write_char(fill)?;
+    }
+    Ok(())
+}
+
+// Writes the failure state for any of the formatting traits, padded like a number.
+// `Debug` quotes it, as for a string.
+pub(crate) fn fmt_overflow(f: &mut fmt::Formatter, debug: bool) -> fmt::Result {
+    fmt_failure(f, "overflow", debug)
+}
+
+// Writes a failed state described by `text`, as `fmt_overflow` does.
+pub(crate) fn fmt_failure(f: &mut fmt::Formatter, text: &str, debug: bool) -> fmt::Result {
+    if debug {
+        pad_numeric(f, text.len() + 2, |f| write!(f, "\"{}\"", text))
+    } else {
+        pad_numeric(f, text.len(), |f| f.write_str(text))
+    }
+}
+
+// implements the radix and exponent formatting traits
+macro_rules! impl_fmt {
+    ($($imp:ident)*) => {
+        $(
+            impl<T: fmt::$imp> fmt::$imp for Checked<T> {
+                #[inline]
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    match **self {
+                        Some(ref x) => x.fmt(f),
+                        None => fmt_overflow(f, false),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_fmt! { LowerHex UpperHex Octal Binary LowerExp UpperExp }
+
 // I'd like to do
 // `impl<T, U> From<U> where T: From<U> for Checked<T>``
 // in the obvious way, but that "conflicts" with the default `impl From<T> for T`.
@@ -89,6 +200,7 @@ impl<T> DerefMut for Checked<T> {
 }
 
 impl<T: PartialOrd> PartialOrd for Checked<T> {
+    #[allow(clippy::needless_borrowed_reference)]
     fn partial_cmp(&self, other: &Checked<T>) -> Option<Ordering> {
         // I'm not really sure why we can't match **self etc. here.
         // Even with refs everywhere it complains
@@ -113,6 +225,7 @@ where
         Checked(Some(T::zero()))
     }
 
+    #[allow(clippy::unnecessary_map_or)]
     fn is_zero(&self) -> bool {
         self.deref().as_ref().map_or(false, Zero::is_zero)
     }
@@ -130,12 +243,21 @@ where
 
 // implements the unary operator `op &T`
 // based on `op T` where `T` is expected to be `Copy`able
+// the generics, if any, go in square brackets, and attributes for the methods before `impl`,
+// and a where clause can follow in square brackets
 macro_rules! forward_ref_unop {
     (impl $imp:ident, $method:ident for $t:ty {}) => {
-        impl<'a> $imp for &'a $t {
+        forward_ref_unop! { impl [] $imp, $method for $t where [] {} }
+    };
+    ($(#[$attr:meta])* impl [$($g:tt)*] $imp:ident, $method:ident for $t:ty {}) => {
+        forward_ref_unop! { $(#[$attr])* impl [$($g)*] $imp, $method for $t where [] {} }
+    };
+    ($(#[$attr:meta])* impl [$($g:tt)*] $imp:ident, $method:ident for $t:ty where [$($w:tt)*] {}) => {
+        impl<'a, $($g)*> $imp for &'a $t where $($w)* {
             type Output = <$t as $imp>::Output;
 
             #[inline]
+            $(#[$attr])*
             fn $method(self) -> <$t as $imp>::Output {
                 $imp::$method(*self)
             }
@@ -145,30 +267,41 @@ macro_rules! forward_ref_unop {
 
 // implements binary operators "&T op U", "T op &U", "&T op &U"
 // based on "T op U" where T and U are expected to be `Copy`able
+// the generics, if any, go in square brackets, and attributes for the methods before `impl`,
+// and a where clause can follow in square brackets
 macro_rules! forward_ref_binop {
     (impl $imp:ident, $method:ident for $t:ty, $u:ty {}) => {
-        impl<'a> $imp<$u> for &'a $t {
+        forward_ref_binop! { impl [] $imp, $method for $t, $u where [] {} }
+    };
+    ($(#[$attr:meta])* impl [$($g:tt)*] $imp:ident, $method:ident for $t:ty, $u:ty {}) => {
+        forward_ref_binop! { $(#[$attr])* impl [$($g)*] $imp, $method for $t, $u where [] {} }
+    };
+    ($(#[$attr:meta])* impl [$($g:tt)*] $imp:ident, $method:ident for $t:ty, $u:ty where [$($w:tt)*] {}) => {
+        impl<'a, $($g)*> $imp<$u> for &'a $t where $($w)* {
             type Output = <$t as $imp<$u>>::Output;
 
             #[inline]
+            $(#[$attr])*
             fn $method(self, other: $u) -> <$t as $imp<$u>>::Output {
                 $imp::$method(*self, other)
             }
         }
 
-        impl<'a> $imp<&'a $u> for $t {
+        impl<'a, $($g)*> $imp<&'a $u> for $t where $($w)* {
             type Output = <$t as $imp<$u>>::Output;
 
             #[inline]
+            $(#[$attr])*
             fn $method(self, other: &'a $u) -> <$t as $imp<$u>>::Output {
                 $imp::$method(self, *other)
             }
         }
 
-        impl<'a, 'b> $imp<&'a $u> for &'b $t {
+        impl<'a, 'b, $($g)*> $imp<&'a $u> for &'b $t where $($w)* {
             type Output = <$t as $imp<$u>>::Output;
 
             #[inline]
+            $(#[$attr])*
             fn $method(self, other: &'a $u) -> <$t as $imp<$u>>::Output {
                 $imp::$method(*self, *other)
             }
@@ -176,115 +309,146 @@ macro_rules! forward_ref_binop {
     }
 }
 
+// implements the shifts of a checked type `$c` of `$t`, by `$f` and its checked type `$cf`
+// the generics, if any, go in square brackets, and `$p` is the policy that failures go to
 macro_rules! impl_sh {
     ($t:ident, $f:ident) => {
-        impl Shl<Checked<$f>> for Checked<$t> {
-            type Output = Checked<$t>;
+        impl_sh! { impl [] $t, $f for Checked<$t>, Checked<$f>, policy::Checked }
+    };
+    (impl [$($g:tt)*] $t:ident, $f:ident for $c:ty, $cf:ty, $p:ty) => {
+        impl<$($g)*> Shl<$cf> for $c {
+            type Output = $c;
 
-            fn shl(self, other: Checked<$f>) -> Checked<$t> {
+            #[track_caller]
+            fn shl(self, other: $cf) -> $c {
                 match (*self, *other) {
-                    (Some(x), Some(y)) => Checked(x.checked_shl(y)),
-                    _ => Checked(None),
+                    (Some(x), Some(y)) => <$c>::from(policy::check::<$p, _, _, _>(x.checked_shl(y), Op::Shl, x, Some(y), || {
+                        (Some(x.wrapping_shl(y)), None)
+                    })),
+                    _ => <$c>::OVERFLOW,
                 }
             }
         }
 
-        impl Shl<$f> for Checked<$t> {
-            type Output = Checked<$t>;
+        impl<$($g)*> Shl<$f> for $c {
+            type Output = $c;
 
-            fn shl(self, other: $f) -> Checked<$t> {
+            #[track_caller]
+            fn shl(self, other: $f) -> $c {
                 match *self {
-                    Some(x) => Checked(x.checked_shl(other)),
-                    None => Checked(None),
+                    Some(x) => <$c>::from(policy::check::<$p, _, _, _>(x.checked_shl(other), Op::Shl, x, Some(other), || {
+                        (Some(x.wrapping_shl(other)), None)
+                    })),
+                    None => <$c>::OVERFLOW,
                 }
             }
         }
 
-        forward_ref_binop! { impl Shl, shl for Checked<$t>, Checked<$f> {} }
-        forward_ref_binop! { impl Shl, shl for Checked<$t>, $f {} }
+        forward_ref_binop! { #[track_caller] impl [$($g)*] Shl, shl for $c, $cf {} }
+        forward_ref_binop! { #[track_caller] impl [$($g)*] Shl, shl for $c, $f {} }
 
-        impl ShlAssign<$f> for Checked<$t> {
+        impl<$($g)*> ShlAssign<$f> for $c {
             #[inline]
+            #[track_caller]
             fn shl_assign(&mut self, other: $f) {
                 *self = *self << other;
             }
         }
 
-        impl ShlAssign<Checked<$f>> for Checked<$t> {
+        impl<$($g)*> ShlAssign<$cf> for $c {
             #[inline]
-            fn shl_assign(&mut self, other: Checked<$f>) {
+            #[track_caller]
+            fn shl_assign(&mut self, other: $cf) {
                 *self = *self << other;
             }
         }
 
-        impl Shr<Checked<$f>> for Checked<$t> {
-            type Output = Checked<$t>;
+        impl<$($g)*> Shr<$cf> for $c {
+            type Output = $c;
 
-            fn shr(self, other: Checked<$f>) -> Checked<$t> {
+            #[track_caller]
+            fn shr(self, other: $cf) -> $c {
                 match (*self, *other) {
-                    (Some(x), Some(y)) => Checked(x.checked_shr(y)),
-                    _ => Checked(None),
+                    (Some(x), Some(y)) => <$c>::from(policy::check::<$p, _, _, _>(x.checked_shr(y), Op::Shr, x, Some(y), || {
+                        (Some(x.wrapping_shr(y)), None)
+                    })),
+                    _ => <$c>::OVERFLOW,
                 }
             }
         }
 
-        impl Shr<$f> for Checked<$t> {
-            type Output = Checked<$t>;
+        impl<$($g)*> Shr<$f> for $c {
+            type Output = $c;
 
-            fn shr(self, other: $f) -> Checked<$t> {
+            #[track_caller]
+            fn shr(self, other: $f) -> $c {
                 match *self {
-                    Some(x) => Checked(x.checked_shr(other)),
-                    None => Checked(None),
+                    Some(x) => <$c>::from(policy::check::<$p, _, _, _>(x.checked_shr(other), Op::Shr, x, Some(other), || {
+                        (Some(x.wrapping_shr(other)), None)
+                    })),
+                    None => <$c>::OVERFLOW,
                 }
             }
         }
 
-        forward_ref_binop! { impl Shr, shr for Checked<$t>, Checked<$f> {} }
-        forward_ref_binop! { impl Shr, shr for Checked<$t>, $f {} }
+        forward_ref_binop! { #[track_caller] impl [$($g)*] Shr, shr for $c, $cf {} }
+        forward_ref_binop! { #[track_caller] impl [$($g)*] Shr, shr for $c, $f {} }
 
-        impl ShrAssign<$f> for Checked<$t> {
+        impl<$($g)*> ShrAssign<$f> for $c {
             #[inline]
+            #[track_caller]
             fn shr_assign(&mut self, other: $f) {
                 *self = *self >> other;
             }
         }
 
-        impl ShrAssign<Checked<$f>> for Checked<$t> {
+        impl<$($g)*> ShrAssign<$cf> for $c {
             #[inline]
-            fn shr_assign(&mut self, other: Checked<$f>) {
+            #[track_caller]
+            fn shr_assign(&mut self, other: $cf) {
                 *self = *self >> other;
             }
         }
     };
 }
 
+// implements the shifts of `$f` by a checked `$t`, giving the checked type `$cf`
 macro_rules! impl_sh_reverse {
     ($t:ident, $f:ident) => {
-        impl Shl<Checked<$t>> for $f {
-            type Output = Checked<$f>;
+        impl_sh_reverse! { impl [] $t, $f for Checked<$t>, Checked<$f>, policy::Checked }
+    };
+    (impl [$($g:tt)*] $t:ident, $f:ident for $c:ty, $cf:ty, $p:ty) => {
+        impl<$($g)*> Shl<$c> for $f {
+            type Output = $cf;
 
-            fn shl(self, other: Checked<$t>) -> Checked<$f> {
+            #[track_caller]
+            fn shl(self, other: $c) -> $cf {
                 match *other {
-                    Some(x) => Checked(self.checked_shl(x)),
-                    None => Checked(None),
+                    Some(x) => <$cf>::from(policy::check::<$p, _, _, _>(self.checked_shl(x), Op::Shl, self, Some(x), || {
+                        (Some(self.wrapping_shl(x)), None)
+                    })),
+                    None => <$cf>::OVERFLOW,
                 }
             }
         }
 
-        forward_ref_binop! { impl Shl, shl for $f, Checked<$t> {} }
+        forward_ref_binop! { #[track_caller] impl [$($g)*] Shl, shl for $f, $c {} }
 
-        impl Shr<Checked<$t>> for $f {
-            type Output = Checked<$f>;
+        impl<$($g)*> Shr<$c> for $f {
+            type Output = $cf;
 
-            fn shr(self, other: Checked<$t>) -> Checked<$f> {
+            #[track_caller]
+            fn shr(self, other: $c) -> $cf {
                 match *other {
-                    Some(x) => Checked(self.checked_shr(x)),
-                    None => Checked(None),
+                    Some(x) => <$cf>::from(policy::check::<$p, _, _, _>(self.checked_shr(x), Op::Shr, self, Some(x), || {
+                        (Some(self.wrapping_shr(x)), None)
+                    })),
+                    None => <$cf>::OVERFLOW,
                 }
             }
         }
 
-        forward_ref_binop! { impl Shr, shr for $f, Checked<$t> {} }
+        forward_ref_binop! { #[track_caller] impl [$($g)*] Shr, shr for $f, $c {} }
     };
 }
 
@@ -320,137 +484,156 @@ macro_rules! impl_sh_all {
 impl_sh_all! { u8 u16 u32 u64 usize i8 i16 i32 i64 isize }
 
 // implements unary operators for checked types
+// `fallback` gives the wrapped and saturated results for the policy
 macro_rules! impl_unop {
-    (impl $imp:ident, $method:ident, $checked_method:ident for $t:ty {}) => {
-        impl $imp for Checked<$t> {
-            type Output = Checked<$t>;
-
-            fn $method(self) -> Checked<$t> {
-                match *self {
-                    Some(x) => Checked(x.$checked_method()),
-                    None => Checked(None)
-                }
-            }
-        }
-
-        forward_ref_unop! { impl $imp, $method for Checked<$t> {} }
-    }
-}
-
-// implements unary operators for checked types (with no checked method)
-macro_rules! impl_unop_unchecked {
-    (impl $imp:ident, $method:ident for $t:ty {$op:tt}) => {
-        impl $imp for Checked<$t> {
-            type Output = Checked<$t>;
+    (impl [$($g:tt)*] $imp:ident, $method:ident, $checked_method:ident for $c:ty, $p:ty: |$x:ident| $fallback:expr) => {
+        impl<$($g)*> $imp for $c {
+            type Output = $c;
 
-            fn $method(self) -> Checked<$t> {
+            #[track_caller]
+            fn $method(self) -> $c {
                 match *self {
-                    Some(x) => Checked(Some($op x)),
-                    None => Checked(None)
+                    Some($x) => <$c>::from(policy::check::<$p, _, _, _>($x.$checked_method(), Op::$imp, $x, None::<u8>, || $fallback)),
+                    None => <$c>::OVERFLOW,
                 }
             }
         }
 
-        forward_ref_unop! { impl $imp, $method for Checked<$t> {} }
+        forward_ref_unop! { #[track_caller] impl [$($g)*] $imp, $method for $c {} }
     }
 }
 
 // implements binary operators for checked types
+// a failure is reported to the hooks, at the caller's location, and then goes to the policy,
+// with the wrapped and saturated results that `fallback` gives
 macro_rules! impl_binop {
-    (impl $imp:ident, $method:ident, $checked_method:ident for $t:ty {}) => {
-        impl $imp for Checked<$t> {
-            type Output = Checked<$t>;
+    (impl [$($g:tt)*] $imp:ident, $method:ident, $checked_method:ident for $c:ty, $t:ty, $p:ty: |$x:ident, $y:ident| $fallback:expr) => {
+        impl<$($g)*> $imp for $c {
+            type Output = $c;
 
-            fn $method(self, other: Checked<$t>) -> Checked<$t> {
+            #[track_caller]
+            fn $method(self, other: $c) -> $c {
                 match (*self, *other) {
-                    (Some(x), Some(y)) => Checked(x.$checked_method(y)),
-                    _ => Checked(None),
+                    (Some($x), Some($y)) => <$c>::from(policy::check::<$p, _, _, _>($x.$checked_method($y), Op::$imp, $x, Some($y), || $fallback)),
+                    _ => <$c>::OVERFLOW,
                 }
             }
         }
 
-        impl $imp<$t> for Checked<$t> {
-            type Output = Checked<$t>;
+        impl<$($g)*> $imp<$t> for $c {
+            type Output = $c;
 
-            fn $method(self, other: $t) -> Checked<$t> {
-                match *self {
-                    Some(x) => Checked(x.$checked_method(other)),
-                    _ => Checked(None),
-                }
+            #[track_caller]
+            fn $method(self, other: $t) -> $c {
+                $imp::$method(self, <$c>::from(other))
             }
         }
 
-        impl $imp<Checked<$t>> for $t {
-            type Output = Checked<$t>;
+        impl<$($g)*> $imp<$c> for $t {
+            type Output = $c;
 
-            fn $method(self, other: Checked<$t>) -> Checked<$t> {
-                match *other {
-                    Some(x) => Checked(self.$checked_method(x)),
-                    None => Checked(None),
-                }
+            #[track_caller]
+            fn $method(self, other: $c) -> $c {
+                $imp::$method(<$c>::from(self), other)
             }
         }
 
-        forward_ref_binop! { impl $imp, $method for Checked<$t>, Checked<$t> {} }
-        forward_ref_binop! { impl $imp, $method for Checked<$t>, $t {} }
-        forward_ref_binop! { impl $imp, $method for $t, Checked<$t> {} }
+        forward_ref_binop! { #[track_caller] impl [$($g)*] $imp, $method for $c, $c {} }
+        forward_ref_binop! { #[track_caller] impl [$($g)*] $imp, $method for $c, $t {} }
+        forward_ref_binop! { #[track_caller] impl [$($g)*] $imp, $method for $t, $c {} }
     }
 }
 
-// implements binary operators for checked types (no checked method)
-macro_rules! impl_binop_unchecked {
-    (impl $imp:ident, $method:ident for $t:ty {$op:tt}) => {
-        impl $imp for Checked<$t> {
-            type Output = Checked<$t>;
+// implements the reverse forms of a bitwise operator, `$t op $c`, for a primitive `$t`
+// the others are generic, see `impl_bitop!`
+macro_rules! impl_bitop_reverse {
+    (impl [$($g:tt)*] $imp:ident, $method:ident for $c:ty, $t:ty) => {
+        impl<$($g)*> $imp<$c> for $t {
+            type Output = $c;
 
-            fn $method(self, other: Checked<$t>) -> Checked<$t> {
-                match (*self, *other) {
-                    (Some(x), Some(y)) => Checked(Some(x $op y)),
-                    _ => Checked(None),
-                }
+            fn $method(self, other: $c) -> $c {
+                $imp::$method(<$c>::from(self), other)
             }
         }
 
-        impl $imp<$t> for Checked<$t> {
-            type Output = Checked<$t>;
+        forward_ref_binop! { impl [$($g)*] $imp, $method for $t, $c {} }
+    }
+}
+
+// implements a bitwise operator and its assignment for the checked type `$c` of any `$t` that
+// has it, with the reference forms
+// these can't fail, so they only need the operator of `$t`, and the result is never reported
+macro_rules! impl_bitop {
+    (impl [$($g:tt)*] Not, not for $c:ty, $t:ident) => {
+        impl<$($g)*> Not for $c where $t: Not<Output = $t> {
+            type Output = $c;
 
-            fn $method(self, other: $t) -> Checked<$t> {
+            #[inline]
+            fn not(self) -> $c {
                 match *self {
-                    Some(x) => Checked(Some(x $op other)),
-                    _ => Checked(None),
+                    Some(x) => <$c>::from(Some(!x)),
+                    None => <$c>::OVERFLOW,
                 }
             }
         }
 
-        impl $imp<Checked<$t>> for $t {
-            type Output = Checked<$t>;
+        forward_ref_unop! { impl [$($g)*] Not, not for $c where [$t: Not<Output = $t>] {} }
+    };
+    (impl [$($g:tt)*] $imp:ident, $method:ident, $imp_assign:ident, $method_assign:ident for $c:ty, $t:ident {$op:tt}) => {
+        impl<$($g)*> $imp for $c where $t: $imp<Output = $t> {
+            type Output = $c;
 
-            fn $method(self, other: Checked<$t>) -> Checked<$t> {
-                match *other {
-                    Some(x) => Checked(Some(self $op x)),
-                    None => Checked(None),
+            #[inline]
+            fn $method(self, other: $c) -> $c {
+                match (*self, *other) {
+                    (Some(x), Some(y)) => <$c>::from(Some(x $op y)),
+                    _ => <$c>::OVERFLOW,
                 }
             }
         }
 
-        forward_ref_binop! { impl $imp, $method for Checked<$t>, Checked<$t> {} }
-        forward_ref_binop! { impl $imp, $method for Checked<$t>, $t {} }
-        forward_ref_binop! { impl $imp, $method for $t, Checked<$t> {} }
-    }
+        impl<$($g)*> $imp<$t> for $c where $t: $imp<Output = $t> {
+            type Output = $c;
+
+            #[inline]
+            fn $method(self, other: $t) -> $c {
+                $imp::$method(self, <$c>::from(other))
+            }
+        }
+
+        forward_ref_binop! { impl [$($g)*] $imp, $method for $c, $c where [$t: $imp<Output = $t>] {} }
+        forward_ref_binop! { impl [$($g)*] $imp, $method for $c, $t where [$t: $imp<Output = $t>] {} }
+
+        impl<$($g)*> $imp_assign for $c where $t: $imp<Output = $t> {
+            #[inline]
+            fn $method_assign(&mut self, other: $c) {
+                *self = *self $op other;
+            }
+        }
+
+        impl<$($g)*> $imp_assign<$t> for $c where $t: $imp<Output = $t> {
+            #[inline]
+            fn $method_assign(&mut self, other: $t) {
+                *self = *self $op other;
+            }
+        }
+    };
 }
 
 // implements assignment operators for checked types
 macro_rules! impl_binop_assign {
-    (impl $imp:ident, $method:ident for $t:ty {$op:tt}) => {
-        impl $imp for Checked<$t> {
+    (impl [$($g:tt)*] $imp:ident, $method:ident for $c:ty, $t:ty {$op:tt}) => {
+        impl<$($g)*> $imp for $c {
             #[inline]
-            fn $method(&mut self, other: Checked<$t>) {
+            #[track_caller]
+            fn $method(&mut self, other: $c) {
                 *self = *self $op other;
             }
         }
 
-        impl $imp<$t> for Checked<$t> {
+        impl<$($g)*> $imp<$t> for $c {
             #[inline]
+            #[track_caller]
             fn $method(&mut self, other: $t) {
                 *self = *self $op other;
             }
@@ -458,27 +641,212 @@ macro_rules! impl_binop_assign {
     };
 }
 
+// implements the operators of the checked type `$c` of `$t`, except for the shifts and the
+// generic bitwise ones
+// the generics, if any, go in square brackets, and `$p` is the policy that failures go to:
+// `Checked<T>` has the operators of `Arith<T, policy::Checked>`
+macro_rules! impl_ops {
+    (impl [$($g:tt)*] for $c:ty, $t:ident, $p:ty) => {
+        impl_binop! { impl [$($g)*] Add, add, checked_add for $c, $t, $p: |x, y| {
+            (Some(x.wrapping_add(y)), Some(x.saturating_add(y)))
+        } }
+        impl_binop_assign! { impl [$($g)*] AddAssign, add_assign for $c, $t {+} }
+        impl_binop! { impl [$($g)*] Sub, sub, checked_sub for $c, $t, $p: |x, y| {
+            (Some(x.wrapping_sub(y)), Some(x.saturating_sub(y)))
+        } }
+        impl_binop_assign! { impl [$($g)*] SubAssign, sub_assign for $c, $t {-} }
+        impl_binop! { impl [$($g)*] Mul, mul, checked_mul for $c, $t, $p: |x, y| {
+            (Some(x.wrapping_mul(y)), Some(x.saturating_mul(y)))
+        } }
+        impl_binop_assign! { impl [$($g)*] MulAssign, mul_assign for $c, $t {*} }
+        // There are no results of a division by zero.
+        impl_binop! { impl [$($g)*] Div, div, checked_div for $c, $t, $p: |x, y| {
+            if y == 0 {
+                (None, None)
+            } else {
+                (Some(x.wrapping_div(y)), Some(x.saturating_div(y)))
+            }
+        } }
+        impl_binop_assign! { impl [$($g)*] DivAssign, div_assign for $c, $t {/} }
+        // The remainder of the most negative value by -1 fails in no direction, as for
+        // `Directed`, so it has nothing to saturate to either.
+        impl_binop! { impl [$($g)*] Rem, rem, checked_rem for $c, $t, $p: |x, y| {
+            if y == 0 {
+                (None, None)
+            } else {
+                (Some(x.wrapping_rem(y)), None)
+            }
+        } }
+        impl_binop_assign! { impl [$($g)*] RemAssign, rem_assign for $c, $t {%} }
+        impl_bitop_reverse! { impl [$($g)*] BitXor, bitxor for $c, $t }
+        impl_bitop_reverse! { impl [$($g)*] BitOr, bitor for $c, $t }
+        impl_bitop_reverse! { impl [$($g)*] BitAnd, bitand for $c, $t }
+        // Negation saturates to the largest value, or to 0 for unsigned types.
+        impl_unop! { impl [$($g)*] Neg, neg, checked_neg for $c, $p: |x| {
+            (Some(x.wrapping_neg()), Some((0 as $t).saturating_sub(x)))
+        } }
+    };
+}
+
+// implements the `const fn` versions of the operators
+// a `const fn` can't be generic over the primitive (it would need `T`'s destructor),
+// so these are written out for each type like the operators are
+macro_rules! impl_const {
+    ($t:ident) => {
+        // Trait methods can't be `const fn`, so these have their own names (using the operator
+        // names would hide the operators' methods, as in `x.add(1)`).
+        impl Checked<$t> {
+            /// Checked addition that can be used in constant expressions. Otherwise the same as `+`.
+            /// # Examples
+            /// ```
+            /// use checked::Checked;
+            ///
+            #[doc = concat!("const SUM: Checked<", stringify!($t), "> = Checked::<", stringify!($t), ">::new(1).const_add(Checked::new(2));")]
+            /// assert_eq!(SUM, Checked::new(3));
+            #[doc = concat!("const TOO_BIG: Checked<", stringify!($t), "> = Checked::<", stringify!($t), ">::new(", stringify!($t), "::MAX).const_add(Checked::new(1));")]
+            /// assert_eq!(TOO_BIG, Checked::OVERFLOW);
+            /// ```
+            #[inline]
+            pub const fn const_add(self, other: Checked<$t>) -> Checked<$t> {
+                match (self.0, other.0) {
+                    (Some(x), Some(y)) => Checked(x.checked_add(y)),
+                    _ => Checked(None),
+                }
+            }
+
+            /// Checked subtraction that can be used in constant expressions. Otherwise the same as `-`.
+            #[inline]
+            pub const fn const_sub(self, other: Checked<$t>) -> Checked<$t> {
+                match (self.0, other.0) {
+                    (Some(x), Some(y)) => Checked(x.checked_sub(y)),
+                    _ => Checked(None),
+                }
+            }
+
+            /// Checked multiplication that can be used in constant expressions. Otherwise the same as `*`.
+            #[inline]
+            pub const fn const_mul(self, other: Checked<$t>) -> Checked<$t> {
+                match (self.0, other.0) {
+                    (Some(x), Some(y)) => Checked(x.checked_mul(y)),
+                    _ => Checked(None),
+                }
+            }
+
+            /// Checked division that can be used in constant expressions. Otherwise the same as `/`.
+            #[inline]
+            pub const fn const_div(self, other: Checked<$t>) -> Checked<$t> {
+                match (self.0, other.0) {
+                    (Some(x), Some(y)) => Checked(x.checked_div(y)),
+                    _ => Checked(None),
+                }
+            }
+
+            /// Checked remainder that can be used in constant expressions. Otherwise the same as `%`.
+            #[inline]
+            pub const fn const_rem(self, other: Checked<$t>) -> Checked<$t> {
+                match (self.0, other.0) {
+                    (Some(x), Some(y)) => Checked(x.checked_rem(y)),
+                    _ => Checked(None),
+                }
+            }
+
+            /// Checked left shift that can be used in constant expressions. Otherwise the same as `<<`.
+            #[inline]
+            pub const fn const_shl(self, other: u32) -> Checked<$t> {
+                match self.0 {
+                    Some(x) => Checked(x.checked_shl(other)),
+                    None => Checked(None),
+                }
+            }
+
+            /// Checked right shift that can be used in constant expressions. Otherwise the same as `>>`.
+            #[inline]
+            pub const fn const_shr(self, other: u32) -> Checked<$t> {
+                match self.0 {
+                    Some(x) => Checked(x.checked_shr(other)),
+                    None => Checked(None),
+                }
+            }
+
+            /// Checked negation that can be used in constant expressions. Otherwise the same as unary `-`.
+            #[inline]
+            pub const fn const_neg(self) -> Checked<$t> {
+                match self.0 {
+                    Some(x) => Checked(x.checked_neg()),
+                    None => Checked(None),
+                }
+            }
+
+            /// Checked exponentiation.
+            /// # Examples
+            /// ```
+            /// use checked::Checked;
+            ///
+            #[doc = concat!("assert_eq!(Checked::<", stringify!($t), ">::new(2).pow(3), Checked::new(8));")]
+            #[doc = concat!("assert_eq!(Checked::<", stringify!($t), ">::new(2).pow(", stringify!($t), "::BITS), Checked::OVERFLOW);")]
+            /// ```
+            #[inline]
+            pub const fn pow(self, exp: u32) -> Checked<$t> {
+                match self.0 {
+                    Some(x) => Checked(x.checked_pow(exp)),
+                    None => Checked(None),
+                }
+            }
+
+            /// Returns the value, panicking in the overflow state.
+            ///
+            /// In a constant initializer the panic is a compile error, so this is the way to get
+            /// the number out of a `Checked` computed at compile time.
+            /// # Examples
+            /// ```
+            /// use checked::Checked;
+            ///
+            #[doc = concat!("const PAGES: ", stringify!($t), " = Checked::<", stringify!($t), ">::new(3).const_mul(Checked::new(4)).unwrap_const();")]
+            /// assert_eq!(PAGES, 12);
+            /// ```
+            ///
+            /// ```compile_fail
+            /// use checked::Checked;
+            ///
+            #[doc = concat!("const PAGES: ", stringify!($t), " = Checked::<", stringify!($t), ">::new(", stringify!($t), "::MAX).const_mul(Checked::new(4)).unwrap_const();")]
+            /// # let _ = PAGES;
+            /// ```
+            #[inline]
+            pub const fn unwrap_const(self) -> $t {
+                match self.0 {
+                    Some(x) => x,
+                    None => panic!("arithmetic overflow"),
+                }
+            }
+
+            /// Returns the value, panicking with `msg` in the overflow state.
+            /// Like [`unwrap_const`](#method.unwrap_const), this is a compile error in a constant
+            /// initializer.
+            #[inline]
+            pub const fn expect_const(self, msg: &'static str) -> $t {
+                match self.0 {
+                    Some(x) => x,
+                    None => panic!("{}", msg),
+                }
+            }
+        }
+    };
+}
+
 macro_rules! checked_impl {
-    ($($t:ty)*) => {
+    ($($t:ident)*) => {
         $(
-            impl_binop! { impl Add, add, checked_add for $t {} }
-            impl_binop_assign! { impl AddAssign, add_assign for $t {+} }
-            impl_binop! { impl Sub, sub, checked_sub for $t {} }
-            impl_binop_assign! { impl SubAssign, sub_assign for $t {-} }
-            impl_binop! { impl Mul, mul, checked_mul for $t {} }
-            impl_binop_assign! { impl MulAssign, mul_assign for $t {*} }
-            impl_binop! { impl Div, div, checked_div for $t {} }
-            impl_binop_assign! { impl DivAssign, div_assign for $t {/} }
-            impl_binop! { impl Rem, rem, checked_rem for $t {} }
-            impl_binop_assign! { impl RemAssign, rem_assign for $t {%} }
-            impl_unop_unchecked! { impl Not, not for $t {!} }
-            impl_binop_unchecked! { impl BitXor, bitxor for $t {^} }
-            impl_binop_assign! { impl BitXorAssign, bitxor_assign for $t {^} }
-            impl_binop_unchecked! { impl BitOr, bitor for $t {|} }
-            impl_binop_assign! { impl BitOrAssign, bitor_assign for $t {|} }
-            impl_binop_unchecked! { impl BitAnd, bitand for $t {&} }
-            impl_binop_assign! { impl BitAndAssign, bitand_assign for $t {&} }
-            impl_unop! { impl Neg, neg, checked_neg for $t {} }
+            impl_ops! { impl [] for Checked<$t>, $t, policy::Checked }
+            impl_const! { $t }
+
+            impl TryFrom<Checked<$t>> for $t {
+                type Error = OverflowError;
+
+                #[inline]
+                fn try_from(x: Checked<$t>) -> Result<$t, OverflowError> {
+                    x.into_result()
+                }
+            }
 
         )*
     };
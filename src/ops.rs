@@ -0,0 +1,195 @@
+//! Checked arithmetic for types other than the primitive integers.
+//!
+//! `Checked<T>` has the operators whenever `T` implements the traits here: `CheckedAdd` gives
+//! `Checked<T> + Checked<T>`, `Checked<T> + T`, `Checked<T> += T` and so on, along with the
+//! reference forms.
+//! The bitwise operators can't overflow, so they only need the ones of `T`: `Checked<T>` has `&`,
+//! `|`, `^` and `!` whenever a `Copy` type `T` does, with the same forms.
+//!
+//! These are usually derived with `#[derive(CheckedOps)]` (with the `macros` feature), which also
+//! implements the reverse forms such as `Bytes + Checked<Bytes>`.
+//! # Examples
+//! ```
+//! use checked::Checked;
+//! use checked::ops::CheckedAdd;
+//!
+//! #[derive(Clone, Copy, Debug, PartialEq)]
+//! struct Bytes(u64);
+//!
+//! impl CheckedAdd for Bytes {
+//!     fn checked_add(self, other: Bytes) -> Option<Bytes> {
+//!         self.0.checked_add(other.0).map(Bytes)
+//!     }
+//! }
+//!
+//! let total = Checked::new(Bytes(1024)) + Bytes(1024);
+//! assert_eq!(total, Checked::new(Bytes(2048)));
+//! assert_eq!(total + Bytes(u64::MAX), Checked::OVERFLOW);
+//! ```
+
+use core::ops::*;
+
+use num::Checked;
+
+/// Addition that fails on overflow.
+pub trait CheckedAdd: Sized {
+    /// Adds `other`, returning `None` on overflow.
+    fn checked_add(self, other: Self) -> Option<Self>;
+}
+
+/// Subtraction that fails on overflow.
+pub trait CheckedSub: Sized {
+    /// Subtracts `other`, returning `None` on overflow.
+    fn checked_sub(self, other: Self) -> Option<Self>;
+}
+
+/// Multiplication that fails on overflow.
+pub trait CheckedMul: Sized {
+    /// Multiplies by `other`, returning `None` on overflow.
+    fn checked_mul(self, other: Self) -> Option<Self>;
+}
+
+/// Division that fails on overflow or division by zero.
+pub trait CheckedDiv: Sized {
+    /// Divides by `other`, returning `None` on overflow or division by zero.
+    fn checked_div(self, other: Self) -> Option<Self>;
+}
+
+/// Remainder that fails on overflow or division by zero.
+pub trait CheckedRem: Sized {
+    /// Takes the remainder by `other`, returning `None` on overflow or division by zero.
+    fn checked_rem(self, other: Self) -> Option<Self>;
+}
+
+/// Negation that fails on overflow.
+pub trait CheckedNeg: Sized {
+    /// Negates, returning `None` on overflow.
+    fn checked_neg(self) -> Option<Self>;
+}
+
+/// Left shift that fails when the shift amount is too large.
+pub trait CheckedShl: Sized {
+    /// Shifts left by `other` bits, returning `None` if that's too many.
+    fn checked_shl(self, other: u32) -> Option<Self>;
+}
+
+/// Right shift that fails when the shift amount is too large.
+pub trait CheckedShr: Sized {
+    /// Shifts right by `other` bits, returning `None` if that's too many.
+    fn checked_shr(self, other: u32) -> Option<Self>;
+}
+
+// implements a binary operator and its assignment for `Checked<T>` with `T` on the right
+macro_rules! impl_ops_binop {
+    (impl $imp:ident, $method:ident, $imp_assign:ident, $method_assign:ident for $tr:ident, $checked_method:ident) => {
+        impl<T: $tr> $imp for Checked<T> {
+            type Output = Checked<T>;
+
+            fn $method(self, other: Checked<T>) -> Checked<T> {
+                match (self.0, other.0) {
+                    (Some(x), Some(y)) => Checked(x.$checked_method(y)),
+                    _ => Checked(None),
+                }
+            }
+        }
+
+        impl<T: $tr> $imp<T> for Checked<T> {
+            type Output = Checked<T>;
+
+            fn $method(self, other: T) -> Checked<T> {
+                match self.0 {
+                    Some(x) => Checked(x.$checked_method(other)),
+                    None => Checked(None),
+                }
+            }
+        }
+
+        forward_ref_binop! { impl [T: $tr + Copy] $imp, $method for Checked<T>, Checked<T> {} }
+        forward_ref_binop! { impl [T: $tr + Copy] $imp, $method for Checked<T>, T {} }
+
+        impl<T: $tr> $imp_assign for Checked<T> {
+            #[inline]
+            fn $method_assign(&mut self, other: Checked<T>) {
+                self.0 = $imp::$method(Checked(self.0.take()), other).0;
+            }
+        }
+
+        impl<T: $tr> $imp_assign<T> for Checked<T> {
+            #[inline]
+            fn $method_assign(&mut self, other: T) {
+                self.0 = $imp::$method(Checked(self.0.take()), other).0;
+            }
+        }
+    };
+}
+
+impl_ops_binop! { impl Add, add, AddAssign, add_assign for CheckedAdd, checked_add }
+impl_ops_binop! { impl Sub, sub, SubAssign, sub_assign for CheckedSub, checked_sub }
+impl_ops_binop! { impl Mul, mul, MulAssign, mul_assign for CheckedMul, checked_mul }
+impl_ops_binop! { impl Div, div, DivAssign, div_assign for CheckedDiv, checked_div }
+impl_ops_binop! { impl Rem, rem, RemAssign, rem_assign for CheckedRem, checked_rem }
+
+impl<T: CheckedNeg> Neg for Checked<T> {
+    type Output = Checked<T>;
+
+    fn neg(self) -> Checked<T> {
+        match self.0 {
+            Some(x) => Checked(x.checked_neg()),
+            None => Checked(None),
+        }
+    }
+}
+
+forward_ref_unop! { impl [T: CheckedNeg + Copy] Neg, neg for Checked<T> {} }
+
+// implements a shift and its assignment for `Checked<T>`, by `u32` as for the primitives
+macro_rules! impl_ops_sh {
+    (impl $imp:ident, $method:ident, $imp_assign:ident, $method_assign:ident for $tr:ident, $checked_method:ident) => {
+        impl<T: $tr> $imp<Checked<u32>> for Checked<T> {
+            type Output = Checked<T>;
+
+            fn $method(self, other: Checked<u32>) -> Checked<T> {
+                match (self.0, other.0) {
+                    (Some(x), Some(y)) => Checked(x.$checked_method(y)),
+                    _ => Checked(None),
+                }
+            }
+        }
+
+        impl<T: $tr> $imp<u32> for Checked<T> {
+            type Output = Checked<T>;
+
+            fn $method(self, other: u32) -> Checked<T> {
+                match self.0 {
+                    Some(x) => Checked(x.$checked_method(other)),
+                    None => Checked(None),
+                }
+            }
+        }
+
+        forward_ref_binop! { impl [T: $tr + Copy] $imp, $method for Checked<T>, Checked<u32> {} }
+        forward_ref_binop! { impl [T: $tr + Copy] $imp, $method for Checked<T>, u32 {} }
+
+        impl<T: $tr> $imp_assign<Checked<u32>> for Checked<T> {
+            #[inline]
+            fn $method_assign(&mut self, other: Checked<u32>) {
+                self.0 = $imp::$method(Checked(self.0.take()), other).0;
+            }
+        }
+
+        impl<T: $tr> $imp_assign<u32> for Checked<T> {
+            #[inline]
+            fn $method_assign(&mut self, other: u32) {
+                self.0 = $imp::$method(Checked(self.0.take()), other).0;
+            }
+        }
+    };
+}
+
+impl_ops_sh! { impl Shl, shl, ShlAssign, shl_assign for CheckedShl, checked_shl }
+impl_ops_sh! { impl Shr, shr, ShrAssign, shr_assign for CheckedShr, checked_shr }
+
+impl_bitop! { impl [T: Copy] BitAnd, bitand, BitAndAssign, bitand_assign for Checked<T>, T {&} }
+impl_bitop! { impl [T: Copy] BitOr, bitor, BitOrAssign, bitor_assign for Checked<T>, T {|} }
+impl_bitop! { impl [T: Copy] BitXor, bitxor, BitXorAssign, bitxor_assign for Checked<T>, T {^} }
+impl_bitop! { impl [T: Copy] Not, not for Checked<T>, T }
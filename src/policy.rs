@@ -0,0 +1,166 @@
+//! Policies for [`Arith`](../struct.Arith.html): what happens when an operation fails.
+//!
+//! The built-in policies are [`Checked`](struct.Checked.html), [`Saturating`](struct.Saturating.html),
+//! [`Wrapping`](struct.Wrapping.html) and [`Strict`](struct.Strict.html).
+//! Others can be written by implementing [`Policy`](trait.Policy.html).
+//! # Examples
+//! A policy that fails like `Checked`, but only after logging the failure:
+//!
+//! ```
+//! use checked::Arith;
+//! use checked::policy::{Failure, Policy};
+//!
+//! struct Logged;
+//!
+//! impl Policy for Logged {
+//!     fn fail<T>(failure: Failure<T>) -> Option<T> {
+//!         println!("{}", failure);
+//!         None
+//!     }
+//! }
+//!
+//! let x = Arith::<u8, Logged>::new(200);
+//! assert_eq!(x + 100, Arith::OVERFLOW); // prints "u8 arithmetic failed: 200 + 100 (positive overflow)"
+//! ```
+
+use core::fmt;
+
+use failure::{Op, Operation, Reason, Value};
+use hook::{self, Primitive};
+use strict;
+
+/// Decides what happens when an operation fails.
+///
+/// An operation that succeeds never gets here, so the policy costs nothing until then. By the
+/// time one does, the failure has been reported to the [hooks](../hook/index.html).
+pub trait Policy {
+    /// Returns the result of the failed operation, or `None` to put the value in the failed
+    /// state, like a `Checked` that overflowed. It can also panic.
+    #[track_caller]
+    fn fail<T>(failure: Failure<T>) -> Option<T>;
+}
+
+/// A failed operation, as given to [`Policy::fail`](trait.Policy.html#tymethod.fail).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Failure<T> {
+    /// The operation.
+    pub op: Op,
+    /// The left operand, or the only one for negation.
+    pub lhs: Value,
+    /// The right operand (`None` for negation).
+    pub rhs: Option<Value>,
+    /// Why it failed.
+    pub reason: Reason,
+    /// The name of the type, such as `u8`.
+    pub type_name: &'static str,
+    /// The wrapped result, if there is one (there isn't for division by zero).
+    pub wrapped: Option<T>,
+    /// The saturated result, if there is one (there isn't for division by zero, shifts or
+    /// remainders).
+    pub saturated: Option<T>,
+}
+
+impl<T> fmt::Display for Failure<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let operation = Operation {
+            op: self.op,
+            lhs: self.lhs,
+            rhs: self.rhs,
+        };
+        write!(
+            f,
+            "{} arithmetic failed: {} ({})",
+            self.type_name, operation, self.reason
+        )
+    }
+}
+
+// Passes on the result of an operation on valid operands (`y` is `None` for negation). If it
+// failed, that's reported to the hooks and then handed to the policy `P`, with the wrapped and
+// saturated results that `fallback` works out. The operators of `Checked` and `Arith` all come
+// through here, so for `Checked` this is the policy below.
+#[inline]
+#[track_caller]
+pub(crate) fn check<P, T, U, F>(result: Option<T>, op: Op, x: T, y: Option<U>, fallback: F) -> Option<T>
+where
+    P: Policy,
+    T: Primitive + Copy,
+    U: Copy,
+    Value: From<T> + From<U>,
+    F: FnOnce() -> (Option<T>, Option<T>),
+{
+    match hook::check(result, op, x, y) {
+        Some(result) => Some(result),
+        None => {
+            let (wrapped, saturated) = fallback();
+            let (lhs, rhs) = (Value::from(x), y.map(Value::from));
+            P::fail(Failure {
+                op,
+                lhs,
+                rhs,
+                reason: Reason::of(op, lhs, rhs),
+                type_name: T::NAME,
+                wrapped,
+                saturated,
+            })
+        }
+    }
+}
+
+/// Fails like `Checked`: the value goes into the failed state, and stays there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Checked;
+
+impl Policy for Checked {
+    #[inline]
+    fn fail<T>(_: Failure<T>) -> Option<T> {
+        None
+    }
+}
+
+/// Saturates at the smallest or largest value of the type.
+///
+/// Division by zero, shifts that are too large and the remainder of the smallest value by -1 have
+/// nothing to saturate to, so they panic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Saturating;
+
+impl Policy for Saturating {
+    #[inline]
+    #[track_caller]
+    fn fail<T>(failure: Failure<T>) -> Option<T> {
+        match failure.saturated {
+            Some(x) => Some(x),
+            None => panic!("{}", failure),
+        }
+    }
+}
+
+/// Wraps around, like `std::num::Wrapping`.
+///
+/// Shift amounts are masked to the size of the type, and division by zero panics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Wrapping;
+
+impl Policy for Wrapping {
+    #[inline]
+    #[track_caller]
+    fn fail<T>(failure: Failure<T>) -> Option<T> {
+        match failure.wrapped {
+            Some(x) => Some(x),
+            None => panic!("{}", failure),
+        }
+    }
+}
+
+/// Panics at the failing operator, like [`checked::Strict`](../struct.Strict.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Strict;
+
+impl Policy for Strict {
+    #[inline]
+    #[track_caller]
+    fn fail<T>(failure: Failure<T>) -> Option<T> {
+        strict::fail(failure.op, failure.lhs, failure.rhs, failure.type_name)
+    }
+}
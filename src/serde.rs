@@ -0,0 +1,250 @@
+//! Serde support, enabled by the `serde` feature.
+//!
+//! `Checked` serializes like an `Option`: a valid value is the plain number, and the overflow
+//! state is `null` (or whatever the format uses for `None`).
+//!
+//! ```
+//! # extern crate checked;
+//! # extern crate serde_json;
+//! use checked::Checked;
+//!
+//! # fn main() {
+//! assert_eq!(serde_json::to_string(&Checked::from(5_u8)).unwrap(), "5");
+//! assert_eq!(serde_json::to_string(&Checked::<u8>::from(None)).unwrap(), "null");
+//! assert_eq!(serde_json::from_str::<Checked<u8>>("null").unwrap(), Checked::from(None));
+//! # }
+//! ```
+//!
+//! The modules here are for use with `#[serde(with = "...")]`:
+//! [`tagged`](tagged/index.html) spells out which state the value is in, and
+//! [`range_checked`](range_checked/index.html) turns numbers that don't fit into the overflow state
+//! instead of rejecting them.
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use num_traits::FromPrimitive;
+use serde_crate::de::{self, Deserialize, Deserializer, EnumAccess, VariantAccess, Visitor};
+use serde_crate::ser::{Serialize, Serializer};
+
+use num::Checked;
+
+impl<T: Serialize> Serialize for Checked<T> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Checked<T> {
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Checked<T>, D::Error> {
+        Option::deserialize(deserializer).map(Checked)
+    }
+}
+
+/// The tagged form: `{"value": 5}` for a valid value, and `{"overflow": null}` for the overflow
+/// state.
+///
+/// This is an enum as far as serde is concerned, so it works with formats that aren't
+/// self-describing too.
+/// # Examples
+/// ```
+/// # extern crate checked;
+/// # extern crate serde_json;
+/// # #[macro_use]
+/// # extern crate serde_derive;
+/// use checked::Checked;
+///
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// struct Total {
+///     #[serde(with = "checked::serde::tagged")]
+///     bytes: Checked<u32>,
+/// }
+///
+/// # fn main() {
+/// let total = Total { bytes: Checked::from(None) };
+/// let json = serde_json::to_string(&total).unwrap();
+/// assert_eq!(json, r#"{"bytes":{"overflow":null}}"#);
+/// assert_eq!(serde_json::from_str::<Total>(&json).unwrap(), total);
+/// # }
+/// ```
+pub mod tagged {
+    use super::*;
+
+    const NAME: &str = "Checked";
+    const VARIANTS: &[&str] = &["value", "overflow"];
+
+    /// Serializes `value` in the tagged form.
+    pub fn serialize<T, S>(value: &Checked<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        match **value {
+            Some(ref x) => serializer.serialize_newtype_variant(NAME, 0, VARIANTS[0], x),
+            None => serializer.serialize_newtype_variant(NAME, 1, VARIANTS[1], &()),
+        }
+    }
+
+    /// Deserializes a value in the tagged form.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Checked<T>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_enum(NAME, VARIANTS, TaggedVisitor(PhantomData))
+    }
+
+    struct TaggedVisitor<T>(PhantomData<T>);
+
+    impl<'de, T: Deserialize<'de>> Visitor<'de> for TaggedVisitor<T> {
+        type Value = Checked<T>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a value or overflow")
+        }
+
+        fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Checked<T>, A::Error> {
+            let (overflow, variant) = data.variant::<Tag>()?;
+            if overflow.0 {
+                variant.newtype_variant::<()>().map(|()| Checked(None))
+            } else {
+                variant.newtype_variant().map(Checked::new)
+            }
+        }
+    }
+
+    // `true` for the overflow variant.
+    struct Tag(bool);
+
+    impl<'de> Deserialize<'de> for Tag {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Tag, D::Error> {
+            deserializer.deserialize_identifier(TagVisitor)
+        }
+    }
+
+    struct TagVisitor;
+
+    impl<'de> Visitor<'de> for TagVisitor {
+        type Value = Tag;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("`value` or `overflow`")
+        }
+
+        fn visit_u64<E: de::Error>(self, index: u64) -> Result<Tag, E> {
+            match index {
+                0 => Ok(Tag(false)),
+                1 => Ok(Tag(true)),
+                _ => Err(E::invalid_value(de::Unexpected::Unsigned(index), &self)),
+            }
+        }
+
+        fn visit_str<E: de::Error>(self, name: &str) -> Result<Tag, E> {
+            match name {
+                "value" => Ok(Tag(false)),
+                "overflow" => Ok(Tag(true)),
+                _ => Err(E::unknown_variant(name, VARIANTS)),
+            }
+        }
+    }
+}
+
+/// Like the plain form, except that a number which doesn't fit in `T` deserializes to the overflow
+/// state instead of being an error.
+///
+/// Since the width of the number has to be read from the input, this only works with
+/// self-describing formats such as JSON.
+/// # Examples
+/// ```
+/// # extern crate checked;
+/// # extern crate serde_json;
+/// # #[macro_use]
+/// # extern crate serde_derive;
+/// use checked::Checked;
+///
+/// #[derive(Deserialize)]
+/// struct Pixel {
+///     #[serde(with = "checked::serde::range_checked")]
+///     red: Checked<u8>,
+/// }
+///
+/// # fn main() {
+/// assert_eq!(serde_json::from_str::<Pixel>(r#"{"red": 255}"#).unwrap().red, Checked::from(255));
+/// assert_eq!(serde_json::from_str::<Pixel>(r#"{"red": 256}"#).unwrap().red, Checked::from(None));
+/// assert_eq!(serde_json::from_str::<Pixel>(r#"{"red": -1}"#).unwrap().red, Checked::from(None));
+/// # }
+/// ```
+pub mod range_checked {
+    use super::*;
+
+    /// Serializes `value` in the plain form.
+    #[inline]
+    pub fn serialize<T, S>(value: &Checked<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        value.serialize(serializer)
+    }
+
+    /// Deserializes any integer (or `null`), range-checking it into `T`.
+    ///
+    /// Integers too large for the format's integer types, which JSON parsers commonly read as
+    /// floats, are checked the same way. Floats with a fractional part are an error.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Checked<T>, D::Error>
+    where
+        T: FromPrimitive,
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_option(RangeVisitor(PhantomData))
+    }
+
+    struct RangeVisitor<T>(PhantomData<T>);
+
+    impl<'de, T: FromPrimitive> Visitor<'de> for RangeVisitor<T> {
+        type Value = Checked<T>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("an integer or null")
+        }
+
+        fn visit_i64<E: de::Error>(self, x: i64) -> Result<Checked<T>, E> {
+            Ok(Checked(T::from_i64(x)))
+        }
+
+        fn visit_u64<E: de::Error>(self, x: u64) -> Result<Checked<T>, E> {
+            Ok(Checked(T::from_u64(x)))
+        }
+
+        fn visit_i128<E: de::Error>(self, x: i128) -> Result<Checked<T>, E> {
+            Ok(Checked(T::from_i128(x)))
+        }
+
+        fn visit_u128<E: de::Error>(self, x: u128) -> Result<Checked<T>, E> {
+            Ok(Checked(T::from_u128(x)))
+        }
+
+        fn visit_f64<E: de::Error>(self, x: f64) -> Result<Checked<T>, E> {
+            // This is also false for infinities and NaN.
+            if x % 1.0 == 0.0 {
+                Ok(Checked(T::from_f64(x)))
+            } else {
+                Err(E::invalid_value(de::Unexpected::Float(x), &self))
+            }
+        }
+
+        fn visit_none<E: de::Error>(self) -> Result<Checked<T>, E> {
+            Ok(Checked(None))
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<Checked<T>, E> {
+            Ok(Checked(None))
+        }
+
+        fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Checked<T>, D::Error> {
+            deserializer.deserialize_any(self)
+        }
+    }
+}
@@ -0,0 +1,239 @@
+//! Checked arithmetic on whole slices of integers.
+//!
+//! Each operation comes in three forms: one that writes the results to `out` and fails as a
+//! batch, one that works in place and fails as a batch, and one (ending in `_each`) that writes a
+//! `Checked<T>` for every element. `sum` and `dot` reduce a slice to one `Checked<T>`.
+//!
+//! The loops use the wrapping operations and OR the overflow bits together, instead of checking
+//! each element, so that the compiler can vectorize them. After a batch fails, the output holds
+//! the wrapped results. None of these go through the operators of `Checked`, so they don't call
+//! the [hooks](../hook/index.html).
+//!
+//! The functions that take more than one slice, counting the output, panic if their lengths differ.
+//! # Examples
+//! ```
+//! use checked::{slice, Checked};
+//!
+//! let prices = [120_u32, 4_000, 35];
+//! let counts = [3, 2_000_000, 2];
+//!
+//! let mut totals = [0; 3];
+//! assert!(slice::mul(&prices, &counts, &mut totals).is_err());
+//!
+//! let mut each = [Checked::new(0); 3];
+//! slice::mul_each(&prices, &counts, &mut each);
+//! assert_eq!(each, [Checked::new(360), Checked::OVERFLOW, Checked::new(70)]);
+//!
+//! assert_eq!(slice::dot(&prices, &[3, 1_000, 2]), Checked::new(4_000_430));
+//! ```
+
+use num_traits::ops::overflowing::{OverflowingAdd, OverflowingMul, OverflowingSub};
+use num_traits::{WrappingAdd, Zero};
+
+use accumulator::Widen;
+use failure::OverflowError;
+use num::Checked;
+
+/// The integer types that the functions of this module work on.
+pub trait Element: Widen + OverflowingAdd + OverflowingSub + OverflowingMul {}
+
+macro_rules! impl_element {
+    ($($t:ident)*) => {
+        $(
+            impl Element for $t {}
+        )*
+    };
+}
+
+impl_element! { u8 u16 u32 u64 usize i8 i16 i32 i64 isize }
+
+#[inline]
+#[track_caller]
+fn check_len(a: usize, b: usize) {
+    assert!(a == b, "slice lengths differ: {} and {}", a, b);
+}
+
+#[inline]
+fn batch<T>(failed: bool) -> Result<(), OverflowError> {
+    if failed {
+        Err(OverflowError::new::<T>())
+    } else {
+        Ok(())
+    }
+}
+
+// Applies `f` to pairs of elements, writing the results to `out`, and returns whether any failed.
+#[inline]
+#[track_caller]
+fn zip_with<T: Copy, F>(a: &[T], b: &[T], out: &mut [T], f: F) -> bool
+where
+    F: Fn(T, T) -> (T, bool),
+{
+    check_len(a.len(), b.len());
+    check_len(a.len(), out.len());
+    let mut failed = false;
+    for ((o, &x), &y) in out.iter_mut().zip(a).zip(b) {
+        let (value, overflow) = f(x, y);
+        *o = value;
+        failed |= overflow;
+    }
+    failed
+}
+
+// The same in place, with `a` as the output.
+#[inline]
+#[track_caller]
+fn zip_with_assign<T: Copy, F>(a: &mut [T], b: &[T], f: F) -> bool
+where
+    F: Fn(T, T) -> (T, bool),
+{
+    check_len(a.len(), b.len());
+    let mut failed = false;
+    for (x, &y) in a.iter_mut().zip(b) {
+        let (value, overflow) = f(*x, y);
+        *x = value;
+        failed |= overflow;
+    }
+    failed
+}
+
+// The same with a `Checked` result for each pair.
+#[inline]
+#[track_caller]
+fn zip_with_each<T: Copy, F>(a: &[T], b: &[T], out: &mut [Checked<T>], f: F)
+where
+    F: Fn(T, T) -> (T, bool),
+{
+    check_len(a.len(), b.len());
+    check_len(a.len(), out.len());
+    for ((o, &x), &y) in out.iter_mut().zip(a).zip(b) {
+        let (value, overflow) = f(x, y);
+        *o = Checked(if overflow { None } else { Some(value) });
+    }
+}
+
+/// Writes `a[i] + b[i]` to `out[i]`, failing if any of the sums overflows.
+#[track_caller]
+pub fn add<T: Element>(a: &[T], b: &[T], out: &mut [T]) -> Result<(), OverflowError> {
+    batch::<T>(zip_with(a, b, out, |x, y| x.overflowing_add(&y)))
+}
+
+/// Writes `a[i] - b[i]` to `out[i]`, failing if any of the differences overflows.
+#[track_caller]
+pub fn sub<T: Element>(a: &[T], b: &[T], out: &mut [T]) -> Result<(), OverflowError> {
+    batch::<T>(zip_with(a, b, out, |x, y| x.overflowing_sub(&y)))
+}
+
+/// Writes `a[i] * b[i]` to `out[i]`, failing if any of the products overflows.
+#[track_caller]
+pub fn mul<T: Element>(a: &[T], b: &[T], out: &mut [T]) -> Result<(), OverflowError> {
+    batch::<T>(zip_with(a, b, out, |x, y| x.overflowing_mul(&y)))
+}
+
+/// Writes `a[i] * k` to `out[i]`, failing if any of the products overflows.
+#[track_caller]
+pub fn mul_scalar<T: Element>(a: &[T], k: T, out: &mut [T]) -> Result<(), OverflowError> {
+    check_len(a.len(), out.len());
+    let mut failed = false;
+    for (o, &x) in out.iter_mut().zip(a) {
+        let (value, overflow) = x.overflowing_mul(&k);
+        *o = value;
+        failed |= overflow;
+    }
+    batch::<T>(failed)
+}
+
+/// Adds `b[i]` to `a[i]`, failing if any of the sums overflows.
+#[track_caller]
+pub fn add_assign<T: Element>(a: &mut [T], b: &[T]) -> Result<(), OverflowError> {
+    batch::<T>(zip_with_assign(a, b, |x, y| x.overflowing_add(&y)))
+}
+
+/// Subtracts `b[i]` from `a[i]`, failing if any of the differences overflows.
+#[track_caller]
+pub fn sub_assign<T: Element>(a: &mut [T], b: &[T]) -> Result<(), OverflowError> {
+    batch::<T>(zip_with_assign(a, b, |x, y| x.overflowing_sub(&y)))
+}
+
+/// Multiplies `a[i]` by `b[i]`, failing if any of the products overflows.
+#[track_caller]
+pub fn mul_assign<T: Element>(a: &mut [T], b: &[T]) -> Result<(), OverflowError> {
+    batch::<T>(zip_with_assign(a, b, |x, y| x.overflowing_mul(&y)))
+}
+
+/// Multiplies every element of `a` by `k`, failing if any of the products overflows.
+pub fn mul_scalar_assign<T: Element>(a: &mut [T], k: T) -> Result<(), OverflowError> {
+    let mut failed = false;
+    for x in a.iter_mut() {
+        let (value, overflow) = x.overflowing_mul(&k);
+        *x = value;
+        failed |= overflow;
+    }
+    batch::<T>(failed)
+}
+
+/// Writes `a[i] + b[i]` to `out[i]`, or `Checked::OVERFLOW` where the sum overflows.
+#[track_caller]
+pub fn add_each<T: Element>(a: &[T], b: &[T], out: &mut [Checked<T>]) {
+    zip_with_each(a, b, out, |x, y| x.overflowing_add(&y))
+}
+
+/// Writes `a[i] - b[i]` to `out[i]`, or `Checked::OVERFLOW` where the difference overflows.
+#[track_caller]
+pub fn sub_each<T: Element>(a: &[T], b: &[T], out: &mut [Checked<T>]) {
+    zip_with_each(a, b, out, |x, y| x.overflowing_sub(&y))
+}
+
+/// Writes `a[i] * b[i]` to `out[i]`, or `Checked::OVERFLOW` where the product overflows.
+#[track_caller]
+pub fn mul_each<T: Element>(a: &[T], b: &[T], out: &mut [Checked<T>]) {
+    zip_with_each(a, b, out, |x, y| x.overflowing_mul(&y))
+}
+
+/// Writes `a[i] * k` to `out[i]`, or `Checked::OVERFLOW` where the product overflows.
+#[track_caller]
+pub fn mul_scalar_each<T: Element>(a: &[T], k: T, out: &mut [Checked<T>]) {
+    check_len(a.len(), out.len());
+    for (o, &x) in out.iter_mut().zip(a) {
+        let (value, overflow) = x.overflowing_mul(&k);
+        *o = Checked(if overflow { None } else { Some(value) });
+    }
+}
+
+/// The sum of the elements, or `None` if it doesn't fit in `T`.
+///
+/// The sum is done in the wide type, as by an [`Accumulator`](../struct.Accumulator.html), so
+/// only the total has to fit: `sum(&[i8::MAX, 1, -1])` is `i8::MAX`.
+pub fn sum<T: Element>(a: &[T]) -> Checked<T> {
+    if a.len() as u64 > T::LIMIT {
+        return Checked(None);
+    }
+    let sum = a
+        .iter()
+        .fold(T::Wide::zero(), |sum, &x| sum.wrapping_add(&x.widen()));
+    Checked(T::narrow(sum))
+}
+
+/// The sum of `a[i] * b[i]`, or `None` if it doesn't fit in `T`.
+///
+/// Each product has to fit in `T`, but as with [`sum`](fn.sum.html), the products are added in
+/// the wide type, so only the total has to fit.
+#[track_caller]
+pub fn dot<T: Element>(a: &[T], b: &[T]) -> Checked<T> {
+    check_len(a.len(), b.len());
+    if a.len() as u64 > T::LIMIT {
+        return Checked(None);
+    }
+    let (sum, failed) =
+        a.iter()
+            .zip(b)
+            .fold((T::Wide::zero(), false), |(sum, failed), (&x, &y)| {
+                let (product, overflow) = x.overflowing_mul(&y);
+                (sum.wrapping_add(&product.widen()), failed | overflow)
+            });
+    if failed {
+        Checked(None)
+    } else {
+        Checked(T::narrow(sum))
+    }
+}
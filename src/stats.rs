@@ -0,0 +1,174 @@
+//! Counts of `Checked` operations and failures, by operation and type.
+//!
+//! With the `stats` feature, each operator of `Checked`, `Arith` and `Directed` on the primitive
+//! types counts the operations it does on valid operands, and how many of those failed. Operations
+//! on a value that had already failed aren't counted. The counters are process-wide and updated
+//! with relaxed atomic increments, so this adds a little to every operation, which is why it's
+//! optional.
+//!
+//! On targets without 64-bit atomics, such as 32-bit ARM, the counters are `usize`s, and wrap
+//! around after 2<sup>32</sup> operations.
+//! # Examples
+//! ```
+//! use checked::{stats, Checked, Op};
+//!
+//! let before = stats::snapshot();
+//! let x = Checked::new(200_u8);
+//! let _ = x + 50;
+//! let _ = x + 100;
+//! let counts = stats::snapshot().since(&before).get("u8", Op::Add).unwrap();
+//! assert_eq!((counts.operations, counts.failures), (2, 1));
+//! ```
+
+use core::ops::{Add, AddAssign};
+use core::sync::atomic::Ordering;
+#[cfg(target_has_atomic = "64")]
+use core::sync::atomic::AtomicU64 as Counter;
+#[cfg(not(target_has_atomic = "64"))]
+use core::sync::atomic::AtomicUsize as Counter;
+
+use failure::Op;
+use hook::PRIMITIVES;
+
+const OPS: usize = 8;
+const TYPES: usize = 10;
+
+// What the counters hold, which they wrap around at.
+#[cfg(target_has_atomic = "64")]
+type Count = u64;
+#[cfg(not(target_has_atomic = "64"))]
+type Count = usize;
+
+#[allow(clippy::declare_interior_mutable_const)]
+const ZERO: Counter = Counter::new(0);
+
+static OPERATIONS: [Counter; OPS * TYPES] = [ZERO; OPS * TYPES];
+static FAILURES: [Counter; OPS * TYPES] = [ZERO; OPS * TYPES];
+
+#[inline]
+pub(crate) fn record(op: Op, type_index: usize, failed: bool) {
+    let i = type_index * OPS + op as usize;
+    OPERATIONS[i].fetch_add(1, Ordering::Relaxed);
+    if failed {
+        FAILURES[i].fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// How many operations ran, and how many of them failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Counts {
+    /// Operations on valid operands.
+    pub operations: u64,
+    /// The operations that failed.
+    pub failures: u64,
+}
+
+impl Counts {
+    /// The fraction of the operations that failed, or 0 if there were none.
+    pub fn failure_rate(self) -> f64 {
+        if self.operations == 0 {
+            0.0
+        } else {
+            self.failures as f64 / self.operations as f64
+        }
+    }
+}
+
+impl Add for Counts {
+    type Output = Counts;
+
+    #[inline]
+    fn add(self, other: Counts) -> Counts {
+        Counts {
+            operations: self.operations + other.operations,
+            failures: self.failures + other.failures,
+        }
+    }
+}
+
+impl AddAssign for Counts {
+    #[inline]
+    fn add_assign(&mut self, other: Counts) {
+        *self = *self + other;
+    }
+}
+
+/// The counts at one point in time, from [`snapshot`](fn.snapshot.html).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Snapshot {
+    counts: [Counts; OPS * TYPES],
+}
+
+impl Snapshot {
+    /// The counts for one operation on one type, such as `"u32"`, or `None` for a type that
+    /// isn't counted.
+    pub fn get(&self, type_name: &str, op: Op) -> Option<Counts> {
+        let t = PRIMITIVES.iter().position(|&name| name == type_name)?;
+        Some(self.counts[t * OPS + op as usize])
+    }
+
+    /// The counts for one operation, over all types.
+    pub fn by_op(&self, op: Op) -> Counts {
+        (0..TYPES).fold(Counts::default(), |sum, t| sum + self.counts[t * OPS + op as usize])
+    }
+
+    /// The counts for one type, over all operations.
+    pub fn by_type(&self, type_name: &str) -> Option<Counts> {
+        let t = PRIMITIVES.iter().position(|&name| name == type_name)?;
+        Some(self.counts[t * OPS..(t + 1) * OPS].iter().fold(Counts::default(), |sum, &c| sum + c))
+    }
+
+    /// The counts over all operations and types.
+    pub fn total(&self) -> Counts {
+        self.counts.iter().fold(Counts::default(), |sum, &c| sum + c)
+    }
+
+    /// Every type, operation and count, including the zeros.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, Op, Counts)> + '_ {
+        self.counts
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| (PRIMITIVES[i / OPS], Op::ALL[i % OPS], c))
+    }
+
+    /// The counts between `earlier` and this snapshot.
+    ///
+    /// Without a [`reset`](fn.reset.html) in between, these are the operations that ran in that
+    /// time, which is how to measure a piece of code while other threads keep counting.
+    pub fn since(&self, earlier: &Snapshot) -> Snapshot {
+        let mut counts = self.counts;
+        for (c, e) in counts.iter_mut().zip(earlier.counts.iter()) {
+            c.operations = difference(c.operations, e.operations);
+            c.failures = difference(c.failures, e.failures);
+        }
+        Snapshot { counts }
+    }
+}
+
+// How far a counter went from `earlier` to `later`, allowing for it to have wrapped around.
+#[inline]
+#[allow(clippy::unnecessary_cast)] // `Count` is only `u64` on some targets
+fn difference(later: u64, earlier: u64) -> u64 {
+    (later as Count).wrapping_sub(earlier as Count) as u64
+}
+
+/// Reads all the counters.
+///
+/// Each counter is read on its own, so operations running on other threads at the same time
+/// may be counted in some of them and not yet in others.
+#[allow(clippy::unnecessary_cast)] // `Count` is only `u64` on some targets
+pub fn snapshot() -> Snapshot {
+    let mut counts = [Counts::default(); OPS * TYPES];
+    for (i, c) in counts.iter_mut().enumerate() {
+        c.operations = OPERATIONS[i].load(Ordering::Relaxed) as u64;
+        c.failures = FAILURES[i].load(Ordering::Relaxed) as u64;
+    }
+    Snapshot { counts }
+}
+
+/// Sets all the counters back to zero.
+pub fn reset() {
+    for counter in OPERATIONS.iter().chain(FAILURES.iter()) {
+        counter.store(0, Ordering::Relaxed);
+    }
+}
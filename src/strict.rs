@@ -0,0 +1,300 @@
+use core::fmt;
+use core::ops::*;
+
+use num_traits::{One, Zero};
+
+use failure::{Op, Operation, Reason, Value};
+use num::Checked;
+
+/// An integer whose arithmetic panics as soon as it fails.
+///
+/// This has the same operators as `Checked`, but rather than carrying the failure along, the
+/// failing operator panics, with a message naming the operation, its operands and the type.
+/// The panic is reported at the operator in your code, so a failure in a test points straight at
+/// its cause.
+/// # Examples
+/// ```
+/// use checked::Strict;
+///
+/// let x = Strict(1_000_u32);
+/// assert_eq!(x * 3 + 2, Strict(3_002));
+/// ```
+///
+/// ```should_panic
+/// use checked::Strict;
+///
+/// let total = Strict(200_u8);
+/// // panics with "strict u8 arithmetic failed: 200 + 100 (positive overflow)"
+/// let _ = total + 100;
+/// ```
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Default)]
+pub struct Strict<T>(pub T);
+
+impl<T> Strict<T> {
+    /// Creates a new Strict instance. This is the same as `Strict(x)`.
+    #[inline]
+    pub const fn new(x: T) -> Strict<T> {
+        Strict(x)
+    }
+}
+
+impl<T> From<T> for Strict<T> {
+    #[inline]
+    fn from(x: T) -> Strict<T> {
+        Strict(x)
+    }
+}
+
+impl<T> From<Strict<T>> for Checked<T> {
+    #[inline]
+    fn from(x: Strict<T>) -> Checked<T> {
+        Checked(Some(x.0))
+    }
+}
+
+// implements the formatting traits by formatting the value
+macro_rules! impl_strict_fmt {
+    ($($imp:ident)*) => {
+        $(
+            impl<T: fmt::$imp> fmt::$imp for Strict<T> {
+                #[inline]
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    self.0.fmt(f)
+                }
+            }
+        )*
+    };
+}
+
+impl_strict_fmt! { Debug Display LowerHex UpperHex Octal Binary LowerExp UpperExp }
+
+impl<T> Zero for Strict<T>
+where
+    T: Zero,
+    Strict<T>: Add<Strict<T>, Output = Strict<T>>,
+{
+    fn zero() -> Strict<T> {
+        Strict(T::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+}
+
+impl<T> One for Strict<T>
+where
+    T: One,
+    Strict<T>: Mul<Strict<T>, Output = Strict<T>>,
+{
+    fn one() -> Strict<T> {
+        Strict(T::one())
+    }
+}
+
+// Panics for a failed operation, here and for `policy::Strict`.
+// This is kept out of line so that the operators themselves stay small.
+#[cold]
+#[inline(never)]
+#[track_caller]
+pub(crate) fn fail(op: Op, lhs: Value, rhs: Option<Value>, type_name: &'static str) -> ! {
+    panic!(
+        "strict {} arithmetic failed: {} ({})",
+        type_name,
+        Operation { op, lhs, rhs },
+        Reason::of(op, lhs, rhs)
+    )
+}
+
+// implements binary operators for strict types
+macro_rules! impl_strict_binop {
+    (impl $imp:ident, $method:ident, $checked_method:ident, $op:ident for $t:ident) => {
+        impl $imp for Strict<$t> {
+            type Output = Strict<$t>;
+
+            #[inline]
+            #[track_caller]
+            fn $method(self, other: Strict<$t>) -> Strict<$t> {
+                match self.0.$checked_method(other.0) {
+                    Some(x) => Strict(x),
+                    None => fail(Op::$op, Value::from(self.0), Some(Value::from(other.0)), stringify!($t)),
+                }
+            }
+        }
+
+        impl $imp<$t> for Strict<$t> {
+            type Output = Strict<$t>;
+
+            #[inline]
+            #[track_caller]
+            fn $method(self, other: $t) -> Strict<$t> {
+                $imp::$method(self, Strict(other))
+            }
+        }
+
+        impl $imp<Strict<$t>> for $t {
+            type Output = Strict<$t>;
+
+            #[inline]
+            #[track_caller]
+            fn $method(self, other: Strict<$t>) -> Strict<$t> {
+                $imp::$method(Strict(self), other)
+            }
+        }
+
+        forward_ref_binop! { #[track_caller] impl [] $imp, $method for Strict<$t>, Strict<$t> {} }
+        forward_ref_binop! { #[track_caller] impl [] $imp, $method for Strict<$t>, $t {} }
+        forward_ref_binop! { #[track_caller] impl [] $imp, $method for $t, Strict<$t> {} }
+    };
+}
+
+// implements binary operators for strict types (no checked method)
+macro_rules! impl_strict_binop_unchecked {
+    (impl $imp:ident, $method:ident for $t:ident {$op:tt}) => {
+        impl $imp for Strict<$t> {
+            type Output = Strict<$t>;
+
+            #[inline]
+            fn $method(self, other: Strict<$t>) -> Strict<$t> {
+                Strict(self.0 $op other.0)
+            }
+        }
+
+        impl $imp<$t> for Strict<$t> {
+            type Output = Strict<$t>;
+
+            #[inline]
+            fn $method(self, other: $t) -> Strict<$t> {
+                Strict(self.0 $op other)
+            }
+        }
+
+        impl $imp<Strict<$t>> for $t {
+            type Output = Strict<$t>;
+
+            #[inline]
+            fn $method(self, other: Strict<$t>) -> Strict<$t> {
+                Strict(self $op other.0)
+            }
+        }
+
+        forward_ref_binop! { impl $imp, $method for Strict<$t>, Strict<$t> {} }
+        forward_ref_binop! { impl $imp, $method for Strict<$t>, $t {} }
+        forward_ref_binop! { impl $imp, $method for $t, Strict<$t> {} }
+    };
+}
+
+// implements assignment operators for strict types
+macro_rules! impl_strict_binop_assign {
+    (impl $imp:ident, $method:ident for $t:ident, $u:ty {$op:tt}) => {
+        impl $imp<$u> for Strict<$t> {
+            #[inline]
+            #[track_caller]
+            fn $method(&mut self, other: $u) {
+                *self = *self $op other;
+            }
+        }
+    };
+    (impl $imp:ident, $method:ident for $t:ident {$op:tt}) => {
+        impl_strict_binop_assign! { impl $imp, $method for $t, Strict<$t> {$op} }
+        impl_strict_binop_assign! { impl $imp, $method for $t, $t {$op} }
+    };
+}
+
+// implements shifts for strict types, by `u32` as for `Checked`
+macro_rules! impl_strict_sh {
+    (impl $imp:ident, $method:ident, $checked_method:ident, $op:ident, $imp_assign:ident, $method_assign:ident for $t:ident {$sym:tt}) => {
+        impl $imp<u32> for Strict<$t> {
+            type Output = Strict<$t>;
+
+            #[inline]
+            #[track_caller]
+            fn $method(self, other: u32) -> Strict<$t> {
+                match self.0.$checked_method(other) {
+                    Some(x) => Strict(x),
+                    None => fail(Op::$op, Value::from(self.0), Some(Value::from(other)), stringify!($t)),
+                }
+            }
+        }
+
+        impl $imp<Strict<u32>> for Strict<$t> {
+            type Output = Strict<$t>;
+
+            #[inline]
+            #[track_caller]
+            fn $method(self, other: Strict<u32>) -> Strict<$t> {
+                $imp::$method(self, other.0)
+            }
+        }
+
+        impl $imp<Strict<u32>> for $t {
+            type Output = Strict<$t>;
+
+            #[inline]
+            #[track_caller]
+            fn $method(self, other: Strict<u32>) -> Strict<$t> {
+                $imp::$method(Strict(self), other.0)
+            }
+        }
+
+        forward_ref_binop! { #[track_caller] impl [] $imp, $method for Strict<$t>, u32 {} }
+        forward_ref_binop! { #[track_caller] impl [] $imp, $method for Strict<$t>, Strict<u32> {} }
+        forward_ref_binop! { #[track_caller] impl [] $imp, $method for $t, Strict<u32> {} }
+
+        impl_strict_binop_assign! { impl $imp_assign, $method_assign for $t, u32 {$sym} }
+        impl_strict_binop_assign! { impl $imp_assign, $method_assign for $t, Strict<u32> {$sym} }
+    };
+}
+
+macro_rules! strict_impl {
+    ($($t:ident)*) => {
+        $(
+            impl_strict_binop! { impl Add, add, checked_add, Add for $t }
+            impl_strict_binop_assign! { impl AddAssign, add_assign for $t {+} }
+            impl_strict_binop! { impl Sub, sub, checked_sub, Sub for $t }
+            impl_strict_binop_assign! { impl SubAssign, sub_assign for $t {-} }
+            impl_strict_binop! { impl Mul, mul, checked_mul, Mul for $t }
+            impl_strict_binop_assign! { impl MulAssign, mul_assign for $t {*} }
+            impl_strict_binop! { impl Div, div, checked_div, Div for $t }
+            impl_strict_binop_assign! { impl DivAssign, div_assign for $t {/} }
+            impl_strict_binop! { impl Rem, rem, checked_rem, Rem for $t }
+            impl_strict_binop_assign! { impl RemAssign, rem_assign for $t {%} }
+            impl_strict_binop_unchecked! { impl BitXor, bitxor for $t {^} }
+            impl_strict_binop_assign! { impl BitXorAssign, bitxor_assign for $t {^} }
+            impl_strict_binop_unchecked! { impl BitOr, bitor for $t {|} }
+            impl_strict_binop_assign! { impl BitOrAssign, bitor_assign for $t {|} }
+            impl_strict_binop_unchecked! { impl BitAnd, bitand for $t {&} }
+            impl_strict_binop_assign! { impl BitAndAssign, bitand_assign for $t {&} }
+            impl_strict_sh! { impl Shl, shl, checked_shl, Shl, ShlAssign, shl_assign for $t {<<} }
+            impl_strict_sh! { impl Shr, shr, checked_shr, Shr, ShrAssign, shr_assign for $t {>>} }
+
+            impl Neg for Strict<$t> {
+                type Output = Strict<$t>;
+
+                #[inline]
+                #[track_caller]
+                fn neg(self) -> Strict<$t> {
+                    match self.0.checked_neg() {
+                        Some(x) => Strict(x),
+                        None => fail(Op::Neg, Value::from(self.0), None, stringify!($t)),
+                    }
+                }
+            }
+
+            forward_ref_unop! { #[track_caller] impl [] Neg, neg for Strict<$t> {} }
+
+            impl Not for Strict<$t> {
+                type Output = Strict<$t>;
+
+                #[inline]
+                fn not(self) -> Strict<$t> {
+                    Strict(!self.0)
+                }
+            }
+
+            forward_ref_unop! { impl Not, not for Strict<$t> {} }
+        )*
+    };
+}
+
+strict_impl! { u8 u16 u32 u64 usize i8 i16 i32 i64 isize }
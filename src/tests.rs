@@ -1,3 +1,10 @@
+// The operator tests spell negative literals with parentheses, the ordering tests check
+// `!(a <= b)`, and the reference forms are tested as `&a + &b` on purpose.
+#![allow(unused_parens)]
+#![allow(clippy::neg_cmp_op_on_partial_ord, clippy::op_ref)]
+
+use std::prelude::v1::*;
+
 use super::Checked;
 
 #[test]
@@ -261,6 +268,7 @@ fn mut_ref_option_methods() {
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn neutral_elements() {
     use num_traits::{One, Zero};
     assert_eq!(Checked::<u8>::zero(), Checked::<u8>::from(Some(0)));
@@ -269,3 +277,966 @@ fn neutral_elements() {
     assert_eq!(Checked::<u8>::from(Some(5)).is_zero(), false);
     assert_eq!(Checked::<u8>::from(None).is_zero(), false);
 }
+
+#[test]
+fn radix_formatting() {
+    let x = Checked::from(255_u8);
+    let y = Checked::<u8>::from(None);
+    assert_eq!(format!("{:x} {:X} {:o} {:b}", x, x, x, x), "ff FF 377 11111111");
+    assert_eq!(format!("{:#x} {:#X} {:#o} {:#b}", x, x, x, x), "0xff 0xFF 0o377 0b11111111");
+    assert_eq!(format!("{:#06x}", x), "0x00ff");
+    assert_eq!(format!("{:x} {:X} {:o} {:b}", y, y, y, y), "overflow overflow overflow overflow");
+    assert_eq!(format!("{:>10x}|{:<10b}|{:^10o}", y, y, y), "  overflow|overflow  | overflow ");
+    assert_eq!(format!("{:*>#12x}", y), "****overflow");
+    assert_eq!(format!("{:+#012x}", y), "    overflow");
+}
+
+#[test]
+fn exp_formatting() {
+    let x = Checked::from(1_500_u32);
+    let y = Checked::<u32>::from(None);
+    assert_eq!(format!("{:e} {:E}", x, x), "1.5e3 1.5E3");
+    assert_eq!(format!("{:+10e}", x), "    +1.5e3");
+    assert_eq!(format!("{:e} {:E}", y, y), "overflow overflow");
+    assert_eq!(format!("{:+10e}", y), "  overflow");
+}
+
+#[test]
+fn failure_padding() {
+    use super::{Arith, CheckedAcc, CompactChecked};
+
+    // Every formatting trait pads the failure state the same way, like a number.
+    let x = Checked::from(42_u8);
+    let y = Checked::<u8>::from(None);
+    assert_eq!(format!("{:>10}|{:10}|{:10x}", x, y, y), "        42|  overflow|  overflow");
+    assert_eq!(format!("{:<10}|{:^12}|{:+.3}", y, y, y), "overflow  |  overflow  |overflow");
+    assert_eq!(format!("{:12?}|{:<12?}|{:#?}", y, y, y), "  \"overflow\"|\"overflow\"  |\"overflow\"");
+    assert_eq!(format!("{:10}", Arith::<u8, super::policy::Checked>::OVERFLOW), "  overflow");
+    assert_eq!(format!("{:10}", CompactChecked::<u8>::OVERFLOW), "  overflow");
+    assert_eq!(format!("{:10?}", CheckedAcc::new(255_u8) + 1), "\"overflow\"");
+}
+
+#[test]
+fn display_with_options() {
+    use DisplayOptions;
+
+    let x = Checked::from(-1_234_567_i32);
+    let y = Checked::<i32>::from(None);
+    let default = DisplayOptions::new();
+    assert_eq!(x.display_with(default).to_string(), x.to_string());
+    assert_eq!(y.display_with(default).to_string(), y.to_string());
+
+    let options = default.thousands_separator(',');
+    assert_eq!(x.display_with(options).to_string(), "-1,234,567");
+    assert_eq!(Checked::from(123_u8).display_with(options).to_string(), "123");
+    assert_eq!(Checked::from(1_000_u16).display_with(options).to_string(), "1,000");
+    assert_eq!(Checked::from(0_u16).display_with(options).to_string(), "0");
+    assert_eq!(Checked::from(u64::MAX).display_with(options.thousands_separator('\u{2009}')).to_string(),
+               "18\u{2009}446\u{2009}744\u{2009}073\u{2009}709\u{2009}551\u{2009}615");
+
+    let options = options.failure_text("n/a").show_type(true);
+    assert_eq!(x.display_with(options).to_string(), "Checked<i32>(-1,234,567)");
+    assert_eq!(y.display_with(options).to_string(), "Checked<i32>(n/a)");
+    assert_eq!(y.display_with(options.show_reason(true)).to_string(), "Checked<i32>(n/a)");
+
+    let z = super::Directed::new(-2_000_000_000_i32) * 2;
+    assert_eq!(z.display_with(options).to_string(), "Directed<i32>(n/a)");
+    assert_eq!(z.display_with(options.show_reason(true)).to_string(), "Directed<i32>(n/a (negative overflow))");
+    assert_eq!(format!("{:#}", z.display_with(default)), "Directed<i32>(overflow (negative overflow))");
+    assert_eq!(format!("{:>30}", z.display_with(default.show_reason(true))), "  overflow (negative overflow)");
+    assert_eq!((z / 0).display_with(default.show_reason(true)).to_string(), "overflow");
+    assert_eq!(super::Directed::new(1_234).display_with(options).to_string(), "Directed<i32>(1,234)");
+}
+
+#[test]
+fn display_with_flags() {
+    use DisplayOptions;
+
+    let x = Checked::from(1_234_u32);
+    let y = Checked::<u8>::from(None);
+    let options = DisplayOptions::new().thousands_separator(',');
+    assert_eq!(format!("{:#}", x.display_with(options)), "Checked<u32>(1,234)");
+    assert_eq!(format!("{:#}", y.display_with(options)), "Checked<u8>(overflow)");
+    assert_eq!(format!("{:>8}|{:<8}|{:^9}", x.display_with(options), x.display_with(options), x.display_with(options)),
+               "   1,234|1,234   |  1,234  ");
+    assert_eq!(format!("{:*>25}", y.display_with(options.show_type(true))), "****Checked<u8>(overflow)");
+
+    // The sign and precision go to the number.
+    assert_eq!(format!("{:+}", x.display_with(options)), "+1,234");
+    assert_eq!(format!("{:>8}", Checked::new(-1_234).display_with(options)), "  -1,234");
+    assert_eq!(format!("{:+9}", x.display_with(options.show_type(true))), "Checked<u32>(+1,234)");
+    assert_eq!(format!("{:+.2}", Checked::new(1_234.5).display_with(options)), "+1234.50");
+    assert_eq!(format!("{:+.2}", y.display_with(options)), "overflow");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_plain() {
+    use serde_json;
+    use serde_test::{assert_tokens, Token};
+
+    assert_tokens(&Checked::from(5_u8), &[Token::Some, Token::U8(5)]);
+    assert_tokens(&Checked::<u8>::from(None), &[Token::None]);
+
+    assert_eq!(serde_json::to_string(&Checked::from(-5_i64)).unwrap(), "-5");
+    assert_eq!(serde_json::to_string(&Checked::<i64>::from(None)).unwrap(), "null");
+    assert_eq!(serde_json::from_str::<Checked<u8>>("255").unwrap(), Checked::from(255));
+    assert_eq!(serde_json::from_str::<Checked<u8>>("null").unwrap(), Checked::from(None));
+    assert!(serde_json::from_str::<Checked<u8>>("256").is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_tagged() {
+    use serde::tagged;
+    use serde_json;
+    use serde_test::{assert_tokens, Token};
+
+    #[derive(Debug, PartialEq)]
+    struct Tagged(Checked<u16>);
+
+    impl ::serde_crate::Serialize for Tagged {
+        fn serialize<S: ::serde_crate::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+            tagged::serialize(&self.0, s)
+        }
+    }
+
+    impl<'de> ::serde_crate::Deserialize<'de> for Tagged {
+        fn deserialize<D: ::serde_crate::Deserializer<'de>>(d: D) -> Result<Tagged, D::Error> {
+            tagged::deserialize(d).map(Tagged)
+        }
+    }
+
+    assert_tokens(
+        &Tagged(Checked::from(500)),
+        &[Token::NewtypeVariant { name: "Checked", variant: "value" }, Token::U16(500)],
+    );
+    assert_tokens(
+        &Tagged(Checked::from(None)),
+        &[Token::NewtypeVariant { name: "Checked", variant: "overflow" }, Token::Unit],
+    );
+
+    assert_eq!(serde_json::to_string(&Tagged(Checked::from(500))).unwrap(), r#"{"value":500}"#);
+    assert_eq!(serde_json::to_string(&Tagged(Checked::from(None))).unwrap(), r#"{"overflow":null}"#);
+    assert_eq!(serde_json::from_str::<Tagged>(r#"{"value":500}"#).unwrap(), Tagged(Checked::from(500)));
+    assert_eq!(serde_json::from_str::<Tagged>(r#"{"overflow":null}"#).unwrap(), Tagged(Checked::from(None)));
+    assert!(serde_json::from_str::<Tagged>(r#"{"underflow":null}"#).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_range_checked() {
+    use serde::range_checked;
+    use serde_json;
+
+    fn from_json<T: ::num_traits::FromPrimitive>(json: &str) -> Checked<T> {
+        range_checked::deserialize(&mut serde_json::Deserializer::from_str(json)).unwrap()
+    }
+
+    assert_eq!(from_json::<u8>("255"), Checked::from(255));
+    assert_eq!(from_json::<u8>("256"), Checked::from(None));
+    assert_eq!(from_json::<u8>("-1"), Checked::from(None));
+    assert_eq!(from_json::<i8>("-128"), Checked::from(-128));
+    assert_eq!(from_json::<i8>("-129"), Checked::from(None));
+    assert_eq!(from_json::<u64>("18446744073709551615"), Checked::from(u64::MAX));
+    assert_eq!(from_json::<u64>("null"), Checked::from(None));
+    assert!(range_checked::deserialize::<u8, _>(&mut serde_json::Deserializer::from_str("1.5")).is_err());
+
+    // integers past `u64` come as floats
+    assert_eq!(from_json::<u64>("18446744073709551616"), Checked::from(None));
+    assert_eq!(from_json::<i64>("-1e30"), Checked::from(None));
+    assert_eq!(from_json::<u8>("2e2"), Checked::from(200));
+    assert!(range_checked::deserialize::<u64, _>(&mut serde_json::Deserializer::from_str("1e-3")).is_err());
+}
+
+#[test]
+fn const_arithmetic() {
+    const X: Checked<u32> = Checked::<u32>::new(4096).const_mul(Checked::new(16)).const_add(Checked::new(7));
+    const Y: Checked<i8> = Checked::<i8>::new(-128).const_neg();
+    const Z: Checked<u16> = Checked::<u16>::new(1).const_shl(15).const_shr(3).const_sub(Checked::new(4096));
+    const W: Checked<i64> = Checked::<i64>::new(10).pow(18).const_div(Checked::new(7)).const_rem(Checked::new(1000));
+    const ZERO: Checked<u8> = Checked::<u8>::new(10).const_div(Checked::new(0));
+    const SIZE: usize = Checked::<usize>::new(64).const_mul(Checked::new(64)).expect_const("buffer size");
+
+    assert_eq!(X, Checked::from(4096 * 16 + 7));
+    assert_eq!(Y, Checked::OVERFLOW);
+    assert_eq!(Z, Checked::from(0));
+    assert_eq!(W, Checked::from(10_i64.pow(18) / 7 % 1000));
+    assert_eq!(ZERO, Checked::OVERFLOW);
+    assert_eq!(SIZE, 4096);
+    assert_eq!(Checked::<u8>::OVERFLOW.const_add(Checked::new(1)), Checked::OVERFLOW);
+    assert_eq!(Checked::<u8>::new(1).const_shl(8), Checked::OVERFLOW);
+    assert_eq!(Checked::<i32>::new(-3).pow(3), Checked::from(-27));
+
+    // the named methods agree with the operators
+    let (a, b) = (Checked::from(200_u8), Checked::from(3_u8));
+    assert_eq!(a.const_add(b), a + b);
+    assert_eq!(a.const_sub(b), a - b);
+    assert_eq!(a.const_mul(b), a * b);
+    assert_eq!(a.const_div(b), a / b);
+    assert_eq!(a.const_rem(b), a % b);
+    assert_eq!(a.const_shl(3), a << 3);
+    assert_eq!(a.const_shr(3), a >> 3);
+    assert_eq!(a.const_neg(), -a);
+
+    // and don't hide the operators' own methods
+    use std::ops::{Add, Mul, Neg};
+    assert_eq!(Checked::new(5_u32).add(3_u32), Checked::new(8));
+    assert_eq!(a.mul(b), Checked::OVERFLOW);
+    assert_eq!(Checked::new(5_i8).neg(), Checked::new(-5));
+}
+
+#[test]
+#[should_panic(expected = "arithmetic overflow")]
+fn unwrap_const_at_runtime() {
+    Checked::<u8>::new(255).const_add(Checked::new(1)).unwrap_const();
+}
+
+#[test]
+fn failure_reasons() {
+    use super::{Op, Reason, Value};
+
+    let reason = |op, x: i8, y: Option<i8>| Reason::of(op, Value::from(x), y.map(Value::from));
+    assert_eq!(reason(Op::Add, 100, Some(100)), Reason::PositiveOverflow);
+    assert_eq!(reason(Op::Add, -100, Some(-100)), Reason::NegativeOverflow);
+    assert_eq!(reason(Op::Sub, 100, Some(-100)), Reason::PositiveOverflow);
+    assert_eq!(reason(Op::Sub, -100, Some(100)), Reason::NegativeOverflow);
+    assert_eq!(reason(Op::Mul, -100, Some(-2)), Reason::PositiveOverflow);
+    assert_eq!(reason(Op::Mul, 100, Some(-2)), Reason::NegativeOverflow);
+    assert_eq!(reason(Op::Div, -128, Some(-1)), Reason::PositiveOverflow);
+    assert_eq!(reason(Op::Rem, 5, Some(0)), Reason::DivisionByZero);
+    assert_eq!(reason(Op::Shl, 1, Some(8)), Reason::ShiftOverflow);
+    assert_eq!(reason(Op::Neg, -128, None), Reason::PositiveOverflow);
+
+    let unsigned = |op, x: u8, y: Option<u8>| Reason::of(op, Value::from(x), y.map(Value::from));
+    assert_eq!(unsigned(Op::Sub, 1, Some(2)), Reason::NegativeOverflow);
+    assert_eq!(unsigned(Op::Neg, 1, None), Reason::NegativeOverflow);
+    assert_eq!(unsigned(Op::Mul, 16, Some(16)), Reason::PositiveOverflow);
+    assert_eq!(unsigned(Op::Div, 1, Some(0)), Reason::DivisionByZero);
+}
+
+#[test]
+fn expr_error_display() {
+    use super::{ExprError, Op, Reason, Value};
+
+    let error = ExprError {
+        expr: "- x",
+        op: Op::Neg,
+        lhs: Value::from(i64::MIN),
+        rhs: None,
+        reason: Reason::PositiveOverflow,
+    };
+    assert_eq!(error.to_string(), "positive overflow in `- x`: -(-9223372036854775808)");
+    assert_eq!(Value::from(u64::MAX).to_string(), "18446744073709551615");
+    assert_eq!(format!("{:?}", Value::from(-1_i8)), "-1");
+}
+
+#[test]
+fn strict_operators() {
+    use super::Strict;
+
+    let x = Strict(100_u8);
+    assert_eq!(x + 50, Strict(150));
+    assert_eq!(2 * x, Strict(200));
+    assert_eq!(&x - &Strict(1), Strict(99));
+    assert_eq!(x / 7 % 5, Strict(4));
+    assert_eq!(x << 1, Strict(200));
+    assert_eq!(1_u8 << Strict(7), Strict(128));
+    assert_eq!(!x & 0xf0 | 1, Strict(0x91));
+    assert_eq!(-Strict(-5_i32), Strict(5));
+
+    let mut y = x;
+    y += 1;
+    y *= Strict(2);
+    y >>= 1;
+    assert_eq!(y, Strict(101));
+    assert_eq!(Checked::from(y), Checked::from(101));
+    assert_eq!(format!("{:?} {:#x}", y, y), "101 0x65");
+}
+
+#[test]
+#[should_panic(expected = "strict u8 arithmetic failed: 200 + 100 (positive overflow)")]
+fn strict_overflow() {
+    let _ = super::Strict(200_u8) + 100;
+}
+
+#[test]
+#[should_panic(expected = "strict i32 arithmetic failed: 5 / 0 (division by zero)")]
+fn strict_division_by_zero() {
+    let mut x = super::Strict(5);
+    x /= 0;
+}
+
+#[test]
+#[should_panic(expected = "strict i64 arithmetic failed: -(-9223372036854775808) (positive overflow)")]
+fn strict_negation() {
+    let _ = -&super::Strict(i64::MIN);
+}
+
+#[test]
+#[should_panic(expected = "strict u16 arithmetic failed: 1 << 16 (shift amount too large)")]
+fn strict_shift() {
+    let _ = super::Strict(1_u16) << super::Strict(16);
+}
+
+#[test]
+fn arith_policies() {
+    use super::policy::{Checked as CheckedPolicy, Saturating, Strict as StrictPolicy, Wrapping};
+    use super::Arith;
+
+    let x = Arith::<i8, CheckedPolicy>::new(100);
+    assert_eq!(x + 27, Arith::new(127));
+    assert_eq!(x + 28, Arith::OVERFLOW);
+    assert_eq!(Checked::<i8>::from(x * 2), Checked::OVERFLOW);
+    assert_eq!(Arith::from(Checked::new(5_i8)) - x, Arith::new(-95));
+
+    let x = x.with_policy::<Saturating>();
+    assert_eq!(x * 2, Arith::new(127));
+    assert_eq!(-x * 2, Arith::new(-128));
+    assert_eq!(-Arith::<i8, Saturating>::new(-128), Arith::new(127));
+    assert_eq!(-Arith::<u8, Saturating>::new(1), Arith::new(0));
+    assert_eq!(Arith::<i8, Saturating>::new(-128) / -1, Arith::new(127));
+    let minus_one = -1;
+    assert_eq!(Arith::<i8, Wrapping>::new(-128) % minus_one, Arith::new(0));
+
+    let mut y = Arith::<u8, Wrapping>::from(std::num::Wrapping(200));
+    y += 100;
+    assert_eq!(y, Arith::new(44));
+    y <<= 9;
+    assert_eq!(y, Arith::new(88));
+    assert_eq!(1_u8 << Arith::<u32, Wrapping>::new(10), Arith::new(4));
+    assert_eq!(&y - &Arith::new(100), Arith::new(244));
+    assert_eq!(format!("{:?} {:#x}", y, y), "88 0x58");
+
+    let z = Arith::<u16, StrictPolicy>::from(super::Strict(3));
+    assert_eq!((z * 3 + 1) ^ 2, Arith::new(8));
+    assert_eq!(format!("{:>9}", Arith::<u16, StrictPolicy>::OVERFLOW), " overflow");
+}
+
+#[test]
+fn arith_user_policy() {
+    use super::policy::{Failure, Policy};
+    use super::{Arith, Op, Reason};
+
+    // Checks what it's given, then fails like `Checked`.
+    struct Expecting;
+
+    impl Policy for Expecting {
+        fn fail<T>(failure: Failure<T>) -> Option<T> {
+            assert_eq!(failure.op, Op::Mul);
+            assert_eq!(failure.reason, Reason::NegativeOverflow);
+            assert_eq!(failure.to_string(), "i16 arithmetic failed: -300 * 200 (negative overflow)");
+            None
+        }
+    }
+
+    let x = Arith::<i16, Expecting>::new(-300);
+    assert_eq!(x * 100, Arith::new(-30_000));
+    assert_eq!(x * 200, Arith::OVERFLOW);
+    assert_eq!(x * 200 + 1, Arith::OVERFLOW);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn arith_hooks() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use super::policy::{Checked as CheckedPolicy, Saturating};
+    use super::{hook, Arith, Op};
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let seen = events.clone();
+    hook::set_local(move |event: &hook::Event| {
+        seen.borrow_mut().push((event.op, event.type_name, event.location.unwrap().line()))
+    });
+
+    let x = Arith::<u8, Saturating>::new(200);
+    let (y, line) = (x + 100, line!());
+    let _ = -Arith::<i8, CheckedPolicy>::new(-128);
+    let _ = Arith::<u32, CheckedPolicy>::OVERFLOW + 1; // already failed, so not reported
+    let _ = x << 1;
+    hook::clear_local();
+
+    assert_eq!(y, Arith::new(255));
+    assert_eq!(*events.borrow(), [(Op::Add, "u8", line), (Op::Neg, "i8", line + 1)]);
+}
+
+#[test]
+#[should_panic(expected = "u32 arithmetic failed: 1 / 0 (division by zero)")]
+fn arith_saturating_division_by_zero() {
+    let _ = super::Arith::<u32, super::policy::Saturating>::new(1) / 0;
+}
+
+#[test]
+#[should_panic(expected = "i8 arithmetic failed: -128 % -1")]
+fn arith_saturating_remainder() {
+    let minus_one = -1;
+    let _ = super::Arith::<i8, super::policy::Saturating>::new(-128) % minus_one;
+}
+
+#[test]
+#[should_panic(expected = "strict i32 arithmetic failed: 1 << 32 (shift amount too large)")]
+fn arith_strict_shift() {
+    let _ = super::Arith::<i32, super::policy::Strict>::new(1) << 32;
+}
+
+#[test]
+fn directed_failures() {
+    use super::{Directed, Direction};
+
+    let x = Directed::new(100_i8);
+    assert_eq!(x + 27, Directed::new(127));
+    assert_eq!((x + 28).direction(), Some(Direction::Positive));
+    assert_eq!((-x - 29).direction(), Some(Direction::Negative));
+    assert_eq!((x / 0).direction(), Some(Direction::Undefined));
+    assert_eq!((x << 8).direction(), Some(Direction::Undefined));
+    assert_eq!((-Directed::new(i8::MIN)).direction(), Some(Direction::Positive));
+    assert_eq!((Directed::new(i8::MIN) / -1).direction(), Some(Direction::Positive));
+    let minus_one = -1;
+    assert_eq!((Directed::new(i8::MIN) % minus_one).direction(), Some(Direction::Undefined));
+    assert_eq!((Directed::new(0_u8) - 1).direction(), Some(Direction::Negative));
+
+    // The direction stays only while it's certain.
+    let up = x * 2;
+    assert_eq!((up + 1).direction(), Some(Direction::Positive));
+    assert_eq!((up - 1).direction(), Some(Direction::Undefined));
+    assert_eq!((up - (-1)).direction(), Some(Direction::Positive));
+    assert_eq!((up * 3).direction(), Some(Direction::Positive));
+    assert_eq!((up * -3).direction(), Some(Direction::Undefined));
+    assert_eq!((up << 1).direction(), Some(Direction::Positive));
+    assert_eq!((up >> 1).direction(), Some(Direction::Undefined));
+    assert_eq!((-up).direction(), Some(Direction::Undefined));
+    let down = -x * 2;
+    assert_eq!((down * -2).direction(), Some(Direction::Positive));
+    assert_eq!((up * down).direction(), Some(Direction::Negative));
+    assert_eq!((down * down).direction(), Some(Direction::Positive));
+    // Unsigned values that went below zero could have been any negative number, so their
+    // product could be anywhere.
+    let below = Directed::new(0_u8) - 1;
+    assert_eq!((below * below).direction(), Some(Direction::Undefined));
+    assert_eq!((below * below).unwrap_or_wrap(), 1);
+    assert_eq!((below * 2).direction(), Some(Direction::Negative));
+    assert_eq!((up - down).direction(), Some(Direction::Positive));
+    assert_eq!((up + down).direction(), Some(Direction::Undefined));
+    assert_eq!((1 - up).direction(), Some(Direction::Undefined));
+
+    assert_eq!(format!("{} {:?} {}", up, down, x / 0), "positive overflow \"negative overflow\" overflow");
+    assert_eq!(format!("{:>18}|{:<#x}|{:02X}", up, x / 0, x), " positive overflow|overflow|64");
+    assert_eq!(Checked::<i8>::from(up), Checked::OVERFLOW);
+    assert_eq!(Directed::from(Checked::new(1_i8)) + x, Directed::new(101));
+    assert_eq!(Directed::<i8>::from(Checked::OVERFLOW), Directed::OVERFLOW);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn directed_hooks() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use super::{hook, Directed, Op};
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let seen = events.clone();
+    hook::set_local(move |event: &hook::Event| {
+        seen.borrow_mut().push((event.op, event.type_name, event.location.unwrap().line()))
+    });
+
+    let x = Directed::new(200_u8);
+    let (y, line) = (x + 100, line!());
+    let _ = -Directed::new(i8::MIN);
+    let _ = y * 2; // already failed, so not reported
+    let _ = &x << 8;
+    hook::clear_local();
+
+    assert_eq!(*events.borrow(), [(Op::Add, "u8", line), (Op::Neg, "i8", line + 1), (Op::Shl, "u8", line + 3)]);
+}
+
+#[test]
+fn directed_recovery() {
+    use super::Directed;
+
+    let x = Directed::new(200_u8);
+    assert_eq!(x.unwrap_or_saturate(), 200);
+    assert_eq!(x.unwrap_or_clamp(0, 100), 100);
+    assert_eq!(x.unwrap_or_wrap(), 200);
+
+    let mut y = x;
+    y += 100;
+    y *= 3;
+    assert_eq!(y.unwrap_or_saturate(), 255);
+    assert_eq!(y.unwrap_or_clamp(10, 20), 20);
+    assert_eq!(y.unwrap_or_wrap(), 44_u8.wrapping_mul(3));
+    assert_eq!((y ^ 1).unwrap_or_wrap(), 44_u8.wrapping_mul(3) ^ 1);
+    assert_eq!((y / 2).0.unwrap_err().wrapped, None);
+
+    let z = Directed::new(-100_i32) * i32::MAX;
+    assert_eq!(z.unwrap_or_saturate(), i32::MIN);
+    assert_eq!(z.unwrap_or_clamp(-5, 5), -5);
+    assert_eq!(z.unwrap_or_wrap(), (-100_i32).wrapping_mul(i32::MAX));
+}
+
+#[test]
+#[should_panic(expected = "no direction")]
+fn directed_undefined_saturation() {
+    let _ = (super::Directed::new(1_u64) / 0).unwrap_or_saturate();
+}
+
+#[test]
+#[should_panic(expected = "no wrapped result")]
+fn directed_undefined_wrap() {
+    let _ = (super::Directed::new(1_u64) % 0).unwrap_or_wrap();
+}
+
+#[test]
+fn overflow_errors() {
+    use std::convert::TryFrom;
+    use super::{Directed, OverflowError};
+
+    let x = Checked::new(250_u8);
+    assert_eq!(x.try_get(), Ok(250));
+    assert_eq!(u8::try_from(x), Ok(250));
+    let error = (x + 10).into_result().unwrap_err();
+    assert_eq!(error, OverflowError { type_name: "u8", reason: None });
+    assert_eq!(error.to_string(), "u8 arithmetic failed");
+    assert_eq!(i64::try_from(Checked::<i64>::OVERFLOW), Err(OverflowError::new::<i64>()));
+
+    let error = (Directed::new(i16::MIN) - 1).into_result().unwrap_err();
+    assert_eq!(error.to_string(), "i16 arithmetic failed (negative overflow)");
+    assert_eq!((Directed::new(1_i16) / 0).into_result().unwrap_err().reason, None);
+    assert_eq!(Directed::new(7_usize).into_result(), Ok(7));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn overflow_error_source() {
+    use std::error::Error;
+
+    let error: Box<dyn Error> = Box::new(Checked::<u8>::OVERFLOW.into_result().unwrap_err());
+    assert_eq!(error.to_string(), "u8 arithmetic failed");
+    assert!(error.source().is_none());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn context_messages() {
+    let rows = Checked::new(3_000_u16).context("never shown");
+    assert!(rows.messages().next().is_none());
+    assert_eq!(rows.to_string(), "3000");
+
+    let cells = (&rows * 30).context("counting cells");
+    assert_eq!(format!("{:?}", cells), "\"counting cells: overflow\"");
+    let mut bytes = (cells.clone() * 2 + rows).with_context(|| format!("sizing {} tables", 2));
+    bytes -= 1;
+    bytes <<= 1;
+    let bytes = (-bytes).context("saving");
+    assert_eq!(bytes.to_string(), "saving: sizing 2 tables: counting cells: overflow");
+    assert_eq!(*bytes, None);
+    assert_eq!(Checked::<u16>::from(bytes), Checked::OVERFLOW);
+
+    // The messages of both operands are kept.
+    let other = Checked::<u16>::OVERFLOW.context("other");
+    assert_eq!((1 + cells + &other).to_string(), "other: counting cells: overflow");
+
+    // Messages only attach to failures.
+    let mut total = Checked::new(1_u16).context("unused");
+    total += 2;
+    total *= super::WithContext::from(3);
+    assert_eq!(total, super::WithContext::from(9));
+
+    // Only the value is compared and hashed.
+    let mut failures = std::collections::HashSet::new();
+    failures.insert(Checked::<u16>::OVERFLOW.context("one"));
+    assert_eq!(Checked::<u16>::OVERFLOW.context("one"), Checked::OVERFLOW.context("two"));
+    assert!(failures.contains(&Checked::OVERFLOW.context("two")));
+    assert_ne!(Checked::new(9_u16).context("one"), Checked::OVERFLOW.context("one"));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn traced_operations() {
+    use super::{Step, Traced, Value};
+
+    let fee = Traced::new(-25_i16) * 4;
+    let mut balance = 1_000 - fee.clone();
+    balance <<= 2;
+    balance = !(-&balance) ^ 3;
+    assert_eq!(*balance, Some(4_396));
+    assert_eq!(
+        balance.log().to_string(),
+        "-25 * 4 = -100\n1000 - -100 = 1100\n1100 << 2 = 4400\n-4400 = -4400\n!(-4400) = 4399\n4399 ^ 3 = 4396\n"
+    );
+    assert_eq!(
+        balance.steps()[0],
+        Step { symbol: "*", lhs: Value::from(-25_i16), rhs: Some(Value::from(4_i16)), result: Some(Value::from(-100_i16)) }
+    );
+
+    // The trace ends at the first failure.
+    let mut x = balance * 100 + &fee;
+    x /= 0;
+    assert_eq!(x.steps().len(), 7);
+    assert_eq!(x.steps()[6].to_string(), "4396 * 100 = overflow");
+    assert_eq!(Checked::<i16>::from(x.clone()), Checked::OVERFLOW);
+    assert_eq!(format!("{:?}", x), "\"overflow\"");
+
+    // So does one with a failed operand on the right.
+    let y = Traced::new(1_i16) + x;
+    assert_eq!(y.steps().len(), 7);
+    let mut z = Traced::<u8>::from(Checked::new(2));
+    z.clear();
+    assert_eq!((z >> 9).log().to_string(), "2 >> 9 = overflow\n");
+
+    // Only the values are compared and hashed.
+    use std::collections::HashSet;
+    assert_eq!(Traced::new(2_u8) + 1, Traced::new(3_u8));
+    assert_eq!(Traced::new(200_u8) * 2, Traced::<u8>::from(Checked::OVERFLOW));
+    assert_ne!(Traced::new(2_u8) + 1, Traced::new(2_u8) + 2);
+    let values: HashSet<_> = vec![Traced::new(3_u8), Traced::new(1_u8) + 2, Traced::new(6_u8) / 2].into_iter().collect();
+    assert_eq!(values.len(), 1);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn local_hook() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use super::{hook, Op, Reason, Value};
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let seen = events.clone();
+    hook::set_local(move |event: &hook::Event| seen.borrow_mut().push(*event));
+
+    let x = Checked::new(-128_i8);
+    let _ = x + 1;
+    let (y, line) = (&x - &1, line!());
+    let _ = y * 2;
+    let mut z = x;
+    z <<= 8;
+    let _ = -x;
+    let _ = 3 % Checked::new(0_i8);
+    hook::clear_local();
+    let _ = x / 0;
+
+    let events = events.borrow();
+    let ops: Vec<_> = events.iter().map(|event| (event.op, event.reason)).collect();
+    assert_eq!(
+        ops,
+        [
+            (Op::Sub, Reason::NegativeOverflow),
+            (Op::Shl, Reason::ShiftOverflow),
+            (Op::Neg, Reason::PositiveOverflow),
+            (Op::Rem, Reason::DivisionByZero),
+        ]
+    );
+    let first = events[0];
+    assert_eq!((first.type_name, first.lhs, first.rhs), ("i8", Value::from(-128_i8), Some(Value::from(1_i8))));
+    let location = first.location.unwrap();
+    assert_eq!((location.file(), location.line()), (file!(), line));
+    assert_eq!(
+        first.to_string(),
+        format!("i8 arithmetic failed: -128 - 1 (negative overflow) at {}", location)
+    );
+}
+
+#[cfg(all(feature = "std", target_has_atomic = "ptr", target_has_atomic = "8"))]
+#[test]
+fn hook_locations_of_wrappers() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::sync::atomic::Ordering::SeqCst;
+    use super::{hook, AtomicChecked, Traced};
+
+    let lines = Rc::new(RefCell::new(Vec::new()));
+    let seen = lines.clone();
+    hook::set_local(move |event: &hook::Event| {
+        let location = event.location.unwrap();
+        assert_eq!(location.file(), file!());
+        seen.borrow_mut().push(location.line());
+    });
+
+    let (_, traced) = (Traced::new(200_u8) + 100, line!());
+    let (_, negated) = (-Traced::new(i8::MIN), line!());
+    let (_, context) = (Checked::new(200_u8).context("adding") * 2, line!());
+    let mut shifted = Checked::new(1_u8).context("shifting");
+    let (_, shift) = (shifted <<= 8, line!());
+    let (_, atomic) = (AtomicChecked::new(0_u8).fetch_sub(1, SeqCst), line!());
+    hook::clear_local();
+
+    assert_eq!(*lines.borrow(), [traced, negated, context, shift, atomic]);
+}
+
+#[test]
+fn global_hook() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use super::{hook, Value};
+
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    // Other tests fail operations at the same time, so only these operands are counted.
+    fn count(event: &hook::Event) {
+        if event.lhs == Value::from(54_321_u16) {
+            COUNT.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    hook::set(count);
+    let x = Checked::new(54_321_u16);
+    let _ = x * 2;
+    let _ = x + x;
+    let _ = x - 1;
+    hook::clear();
+    let _ = x * 3;
+    assert_eq!(COUNT.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn compact_operators() {
+    use std::mem::size_of;
+    use super::compact::{CompactChecked, Min};
+
+    assert_eq!(size_of::<CompactChecked<u64>>(), 8);
+    assert_eq!(size_of::<CompactChecked<i8, Min>>(), 1);
+
+    let x = CompactChecked::<u8>::new(200);
+    assert_eq!(x + 54, CompactChecked::new(254));
+    assert_eq!((x + 55).get(), None);
+    assert!(!(x + 56).is_valid());
+    assert_eq!(x + 56, CompactChecked::OVERFLOW);
+    assert_eq!(&x - &x, CompactChecked::new(0));
+    assert_eq!(1 - x, CompactChecked::OVERFLOW);
+    assert_eq!((x | 0x0f).into_raw(), 0xcf);
+    assert_eq!(!x, CompactChecked::new(55));
+    assert_eq!(x ^ 0x37, CompactChecked::OVERFLOW);
+    assert_eq!(x >> 1, CompactChecked::new(100));
+    assert_eq!(x << 8, CompactChecked::OVERFLOW);
+    assert_eq!(x / 0, CompactChecked::OVERFLOW);
+
+    let mut y = CompactChecked::<i32, Min>::new(i32::MAX);
+    y %= 10;
+    y *= -3;
+    y -= &CompactChecked::new(2);
+    assert_eq!(y, CompactChecked::new(-23));
+    assert_eq!(-CompactChecked::<i32, Min>::new(i32::MIN + 1), CompactChecked::new(i32::MAX));
+    assert_eq!(format!("{:?} {} {:>9x}", y, y, CompactChecked::<i32, Min>::OVERFLOW), "-23 -23  overflow");
+    assert!(CompactChecked::<u8>::new(2) > CompactChecked::new(1));
+    assert!(!(CompactChecked::<u8>::OVERFLOW >= CompactChecked::new(1)));
+
+    // Conversions
+    assert_eq!(Checked::from(y), Checked::new(-23));
+    assert_eq!(CompactChecked::<u16>::from(Checked::new(7)), CompactChecked::new(7));
+    assert_eq!(CompactChecked::<u16>::from(Checked::new(u16::MAX)), CompactChecked::OVERFLOW);
+    assert_eq!(CompactChecked::<u16>::from(Checked::OVERFLOW).to_checked(), Checked::OVERFLOW);
+    assert_eq!(CompactChecked::<u16>::default().into_raw(), u16::MAX);
+}
+
+#[test]
+fn accumulator_flag() {
+    use super::CheckedAcc;
+
+    let mut sum = CheckedAcc::new(0_u8);
+    for x in 0..22 {
+        sum += x;
+    }
+    assert_eq!(sum.to_checked(), Checked::new(231));
+    sum += 25;
+    assert!(sum.overflowed());
+    sum -= 25;
+    assert_eq!(sum.to_checked(), Checked::OVERFLOW);
+    assert_eq!(sum.into_parts(), (231, true));
+    assert_eq!(sum, CheckedAcc::from(Checked::OVERFLOW));
+
+    let x = CheckedAcc::new(-7_i32);
+    assert_eq!(x / 2, CheckedAcc::new(-3));
+    assert_eq!(x % -4, CheckedAcc::new(-3));
+    assert!((x / 0).overflowed());
+    assert!((x % 0).overflowed());
+    let min = CheckedAcc::new(i32::MIN);
+    let minus_one = -1;
+    assert!((min / minus_one).overflowed());
+    assert!((min % minus_one).overflowed());
+    assert!((-min).overflowed());
+    assert!((x << 32).overflowed());
+    assert_eq!(x >> 1, CheckedAcc::new(-4));
+    assert_eq!(!x & 0xff, CheckedAcc::new(6));
+    assert_eq!(&x * &x - 1, CheckedAcc::new(48));
+    assert_eq!(100 - x, CheckedAcc::new(107));
+    assert!((x + CheckedAcc::from(Checked::<i32>::OVERFLOW)).overflowed());
+    assert_eq!(format!("{} {:?}", x, x * i32::MAX), "-7 \"overflow\"");
+}
+
+#[test]
+fn widening_accumulator() {
+    use super::accumulator::Widen;
+    use super::Accumulator;
+
+    assert_eq!(<u32 as Widen>::LIMIT, 1 << 32);
+    assert_eq!(<i8 as Widen>::LIMIT, 1 << 56);
+    assert_eq!(<i64 as Widen>::LIMIT, u64::MAX - 1);
+
+    let mut sum = Accumulator::<u8>::new();
+    sum.extend(vec![200, 200, 100]);
+    assert_eq!(sum.wide(), Checked::new(500));
+    assert_eq!(sum.to_checked(), Checked::OVERFLOW);
+    sum += 0;
+    let halves: Accumulator<u8> = [11, 245].iter().cloned().sum();
+    sum += halves;
+    assert_eq!(sum.count(), 6);
+    assert_eq!(sum.wide(), Checked::new(756));
+
+    let values = [i64::MIN, -1, i64::MAX, 1];
+    let sum: Accumulator<i64> = values.iter().cloned().collect();
+    assert_eq!(sum.to_checked(), Checked::new(-1));
+    let parts = values.chunks(2).map(|c| c.iter().cloned().collect::<Accumulator<i64>>());
+    assert_eq!(parts.sum::<Accumulator<i64>>(), sum);
+
+    let mut sum: Accumulator<usize> = vec![Checked::new(1), Checked::new(2)].into_iter().collect();
+    assert_eq!(Checked::from(sum), Checked::new(3));
+    let before = sum;
+    sum += Checked::OVERFLOW;
+    sum += 4;
+    assert_eq!(sum.count(), 4);
+    assert_eq!(sum.wide(), Checked::OVERFLOW);
+    let mut valid = before;
+    valid.extend(vec![0, 4]);
+    assert_eq!(valid.count(), 4);
+    assert_ne!(sum, valid);
+    assert_eq!((valid + sum).to_checked(), Checked::OVERFLOW);
+    assert_eq!(format!("{:?}", Accumulator::<i16>::new()), "Accumulator { sum: 0, count: 0 }");
+}
+
+#[test]
+fn slice_operations() {
+    use super::slice;
+
+    let a = [100_u8, 200, 3];
+    let b = [100_u8, 55, 4];
+    let mut out = [0; 3];
+    assert_eq!(slice::add(&a, &b, &mut out), Ok(()));
+    assert_eq!(out, [200, 255, 7]);
+    assert!(slice::sub(&a, &b, &mut out).is_err());
+    assert_eq!(out, [0, 145, 255]);
+    let error = slice::mul(&a, &b, &mut out).unwrap_err();
+    assert_eq!(error.type_name, "u8");
+    assert_eq!(out, [16, 248, 12]);
+    assert!(slice::mul_scalar(&a, 2, &mut out).is_err());
+    assert_eq!(slice::mul_scalar(&a, 1, &mut out), Ok(()));
+    assert_eq!(out, a);
+
+    let mut each = [Checked::new(0); 3];
+    slice::sub_each(&b, &a, &mut each);
+    assert_eq!(each, [Checked::new(0), Checked::OVERFLOW, Checked::new(1)]);
+    slice::mul_scalar_each(&a, 2, &mut each);
+    assert_eq!(each, [Checked::new(200), Checked::OVERFLOW, Checked::new(6)]);
+    slice::add_each(&a, &b, &mut each);
+    assert_eq!(each, [Checked::new(200), Checked::new(255), Checked::new(7)]);
+
+    let mut c = [i64::MAX, -5, 7];
+    assert_eq!(slice::add_assign(&mut c, &[0, -5, 7]), Ok(()));
+    assert!(slice::sub_assign(&mut c, &[-1, 0, 0]).is_err());
+    assert_eq!(c, [i64::MIN, -10, 14]);
+    assert_eq!(slice::mul_assign(&mut c, &[0, 3, 3]), Ok(()));
+    assert_eq!(slice::mul_scalar_assign(&mut c, -2), Ok(()));
+    assert_eq!(c, [0, 60, -84]);
+
+    assert_eq!(slice::sum(&[i8::MAX, 1, -1]), Checked::new(i8::MAX));
+    assert_eq!(slice::sum(&[i8::MAX, 1]), Checked::OVERFLOW);
+    assert_eq!(slice::sum::<usize>(&[]), Checked::new(0));
+    assert_eq!(slice::sum(&[u64::MAX; 3]), Checked::OVERFLOW);
+    assert_eq!(slice::dot(&[16_i16, -300], &[1_000, 100]), Checked::new(-14_000));
+    assert_eq!(slice::dot(&[30_i16, 30, -30], &[1_000; 3]), Checked::new(30_000));
+    assert_eq!(slice::dot(&[20_i16, 20], &[1_000; 2]), Checked::OVERFLOW);
+    assert_eq!(slice::dot(&[256_u16, 0], &[256, 1]), Checked::OVERFLOW);
+}
+
+#[test]
+#[should_panic(expected = "slice lengths differ: 2 and 3")]
+fn slice_length_mismatch() {
+    let mut out = [0_u32; 3];
+    let _ = super::slice::add(&[1, 2], &[3, 4], &mut out);
+}
+
+#[test]
+fn array_operators() {
+    use super::CheckedArray;
+
+    let a = CheckedArray::new([7_i16, -3, i16::MIN, 100]);
+    let b = CheckedArray::from([2, 0, -1, 400]);
+    assert_eq!(a + b, CheckedArray::from([Checked::new(9), Checked::new(-3), Checked::OVERFLOW, Checked::new(500)]));
+    assert_eq!((a - b).get(), Some([5, -3, i16::MIN + 1, -300]));
+    assert_eq!((a * b)[3], Checked::OVERFLOW);
+    assert_eq!(&a / &b, CheckedArray::from([Checked::new(3), Checked::OVERFLOW, Checked::OVERFLOW, Checked::new(0)]));
+    assert_eq!((a % b)[0], Checked::new(1));
+    assert_eq!((a & 0xff).get(), Some([7, 0xfd, 0, 100]));
+    assert_eq!((a | b)[1], Checked::new(-3));
+    assert_eq!((a ^ a).get(), Some([0; 4]));
+    assert_eq!((a >> 1).get(), Some([3, -2, i16::MIN / 2, 50]));
+    assert_eq!((a << 16)[0], Checked::OVERFLOW);
+    assert_eq!((-a)[2], Checked::OVERFLOW);
+    assert_eq!((!a).get(), Some([-8, 2, i16::MAX, -101]));
+    assert_eq!(10 - a, CheckedArray::from([Checked::new(3), Checked::new(13), Checked::OVERFLOW, Checked::new(-90)]));
+    assert_eq!((a * Checked::OVERFLOW).0, [Checked::OVERFLOW; 4]);
+
+    let mut c = CheckedArray::new([1_u8, 2, 3]);
+    c += CheckedArray::new([1, 1, 1]);
+    c *= 10;
+    c -= &CheckedArray::new([0, 0, 1]);
+    assert_eq!(c.get(), Some([20, 30, 39]));
+    assert_eq!(c.sum(), Checked::new(89));
+    assert_eq!(c.product(), Checked::OVERFLOW);
+    assert_eq!((c.min(), c.max()), (Checked::new(20), Checked::new(39)));
+    assert!(c.all_valid());
+    c[1] = Checked::OVERFLOW;
+    assert!(!c.all_valid());
+    assert_eq!((c.get(), c.sum(), c.min()), (None, Checked::OVERFLOW, Checked::OVERFLOW));
+
+    let empty = CheckedArray::<u32, 0>::new([]);
+    assert!(empty.all_valid());
+    assert_eq!((empty.sum(), empty.product(), empty.max()), (Checked::new(0), Checked::new(1), Checked::OVERFLOW));
+}
+
+#[cfg(all(target_has_atomic = "ptr", target_has_atomic = "8"))]
+#[test]
+fn atomic_operations() {
+    use std::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed, Release, SeqCst};
+    use super::AtomicChecked;
+
+    let x = AtomicChecked::new(-100_i8);
+    assert_eq!(x.fetch_sub(28, AcqRel), Ok(-100));
+    assert_eq!(x.fetch_sub(1, Release), Err(-128));
+    assert_eq!(x.fetch_mul(-1, SeqCst), Err(-128));
+    assert_eq!(x.fetch_add(28, Relaxed), Ok(-128));
+    assert_eq!(x.fetch_mul(-1, Relaxed), Ok(-100));
+    assert_eq!(x.fetch_update(SeqCst, Acquire, |v| Checked::new(v) / 0), Err(100));
+    assert_eq!(x.fetch_update(SeqCst, Acquire, |v| Checked::new(v) / 4), Ok(100));
+    assert!(!x.is_poisoned());
+    assert_eq!(format!("{:?}", x), "25");
+    assert_eq!(x.into_inner(), Checked::new(25));
+
+    let y = AtomicChecked::sticky(250_u8);
+    assert_eq!(y.fetch_add(5, SeqCst), Ok(250));
+    assert_eq!(y.fetch_add(1, SeqCst), Err(255));
+    assert!(y.is_poisoned());
+    assert_eq!(y.fetch_sub(1, SeqCst), Err(255));
+    y.store(7, SeqCst);
+    assert_eq!(y.load(SeqCst), Checked::OVERFLOW);
+    assert_eq!(format!("{:?}", y), "\"overflow\"");
+    y.clear_poison();
+    assert_eq!(y.fetch_sub(1, SeqCst), Ok(7));
+    assert_eq!(y.load(SeqCst), Checked::new(6));
+}
+
+#[cfg(target_has_atomic = "ptr")]
+#[test]
+fn atomic_usize() {
+    use std::sync::atomic::Ordering::SeqCst;
+    use super::AtomicChecked;
+
+    let z = AtomicChecked::<usize>::default();
+    assert_eq!(z.fetch_sub(1, SeqCst), Err(0));
+}
+
+#[cfg(all(target_has_atomic = "ptr", target_has_atomic = "64"))]
+#[test]
+fn atomic_u64() {
+    use super::AtomicChecked;
+
+    assert_eq!(AtomicChecked::from(3_u64).into_inner(), Checked::new(3));
+}
@@ -0,0 +1,442 @@
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::mem;
+use core::ops::*;
+use std::vec::Vec;
+
+use failure::Value;
+use num::{fmt_overflow, Checked};
+
+/// One operation in the trace of a [`Traced`](struct.Traced.html) value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Step {
+    /// The operator's symbol, such as `+` or `<<`.
+    pub symbol: &'static str,
+    /// The left operand, or the only one for a unary operator.
+    pub lhs: Value,
+    /// The right operand (`None` for a unary operator).
+    pub rhs: Option<Value>,
+    /// The result, or `None` if the operation failed.
+    pub result: Option<Value>,
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.rhs {
+            Some(rhs) => write!(f, "{} {} {}", self.lhs, self.symbol, rhs)?,
+            None if self.lhs.is_negative() => write!(f, "{}({})", self.symbol, self.lhs)?,
+            None => write!(f, "{}{}", self.symbol, self.lhs)?,
+        }
+        match self.result {
+            Some(result) => write!(f, " = {}", result),
+            None => f.write_str(" = overflow"),
+        }
+    }
+}
+
+/// A checked value that records the operations it went through.
+///
+/// This has the operators of `Checked`, and each of them adds a [`Step`](struct.Step.html) with
+/// its operands and result to the trace. When a value is made from two traced ones, its trace
+/// is theirs followed by the new step. The trace ends at the first failure: operations on a
+/// failed value don't add anything.
+///
+/// Recording takes an allocation per value, so this is meant for auditing and debugging, not
+/// for every calculation. It needs the `std` feature.
+///
+/// Comparing and hashing only look at the value, like for `Checked`, so values with different
+/// traces are equal if they came to the same result.
+/// # Examples
+/// ```
+/// use checked::Traced;
+///
+/// let price = Traced::new(1_999_u32);
+/// let total = (price * 3 + 250) * 1_000_000;
+/// assert_eq!(
+///     total.log().to_string(),
+///     "1999 * 3 = 5997\n5997 + 250 = 6247\n6247 * 1000000 = overflow\n",
+/// );
+/// assert_eq!(Traced::new(1_999_u32) + 1, Traced::new(2_000));
+/// ```
+#[derive(Clone)]
+pub struct Traced<T> {
+    value: Option<T>,
+    steps: Vec<Step>,
+}
+
+impl<T> Traced<T> {
+    /// Creates a new Traced instance, with an empty trace.
+    #[inline]
+    pub const fn new(x: T) -> Traced<T> {
+        Traced {
+            value: Some(x),
+            steps: Vec::new(),
+        }
+    }
+
+    /// The operations so far, in order.
+    #[inline]
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// The trace as a log, one operation per line.
+    #[inline]
+    pub fn log(&self) -> Log<'_> {
+        Log { steps: &self.steps }
+    }
+
+    /// Forgets the trace so far, keeping the value.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.steps.clear();
+    }
+
+    // A placeholder to leave behind while an assignment operator takes the value out.
+    const EMPTY: Traced<T> = Traced {
+        value: None,
+        steps: Vec::new(),
+    };
+}
+
+/// The trace of a [`Traced`](struct.Traced.html) value, displayed one operation per line.
+#[derive(Debug, Clone, Copy)]
+pub struct Log<'a> {
+    steps: &'a [Step],
+}
+
+impl<'a> fmt::Display for Log<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for step in self.steps {
+            writeln!(f, "{}", step)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: PartialEq> PartialEq for Traced<T> {
+    #[inline]
+    fn eq(&self, other: &Traced<T>) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Eq> Eq for Traced<T> {}
+
+impl<T: Hash> Hash for Traced<T> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state)
+    }
+}
+
+impl<T> Deref for Traced<T> {
+    type Target = Option<T>;
+
+    #[inline]
+    fn deref(&self) -> &Option<T> {
+        &self.value
+    }
+}
+
+impl<T> From<T> for Traced<T> {
+    #[inline]
+    fn from(x: T) -> Traced<T> {
+        Traced::new(x)
+    }
+}
+
+impl<T> From<Checked<T>> for Traced<T> {
+    #[inline]
+    fn from(x: Checked<T>) -> Traced<T> {
+        Traced {
+            value: x.0,
+            steps: Vec::new(),
+        }
+    }
+}
+
+impl<T> From<Traced<T>> for Checked<T> {
+    #[inline]
+    fn from(x: Traced<T>) -> Checked<T> {
+        Checked(x.value)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Traced<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.value {
+            Some(ref x) => x.fmt(f),
+            None => fmt_overflow(f, true),
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Traced<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.value {
+            Some(ref x) => x.fmt(f),
+            None => fmt_overflow(f, false),
+        }
+    }
+}
+
+// Records a binary operation with its result, unless the trace has already ended.
+#[inline]
+fn record<T, U>(
+    symbol: &'static str,
+    x: Traced<T>,
+    y: Option<U>,
+    mut y_steps: Vec<Step>,
+    result: Option<T>,
+) -> Traced<T>
+where
+    T: Copy,
+    U: Copy,
+    Value: From<T> + From<U>,
+{
+    let mut steps = x.steps;
+    if x.value.is_some() {
+        steps.append(&mut y_steps);
+    }
+    let value = match (x.value, y) {
+        (Some(a), Some(b)) => {
+            steps.push(Step {
+                symbol,
+                lhs: Value::from(a),
+                rhs: Some(Value::from(b)),
+                result: result.map(Value::from),
+            });
+            result
+        }
+        _ => None,
+    };
+    Traced { value, steps }
+}
+
+// implements binary operators by those of `Checked`, including the forms with references
+macro_rules! impl_traced_binop {
+    ($($imp:ident, $method:ident, $imp_assign:ident, $method_assign:ident {$sym:tt};)*) => {
+        $(
+            impl<T: Copy> $imp for Traced<T>
+            where
+                Checked<T>: $imp<T, Output = Checked<T>>,
+                Value: From<T>,
+            {
+                type Output = Traced<T>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: Traced<T>) -> Traced<T> {
+                    // The operator is applied here rather than in `record`, so that the hooks get
+                    // the caller's location.
+                    let result = match (self.value, other.value) {
+                        (Some(x), Some(y)) => $imp::$method(Checked::new(x), y).0,
+                        _ => None,
+                    };
+                    record(stringify!($sym), self, other.value, other.steps, result)
+                }
+            }
+
+            impl<T: Copy> $imp<T> for Traced<T>
+            where
+                Checked<T>: $imp<T, Output = Checked<T>>,
+                Value: From<T>,
+            {
+                type Output = Traced<T>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: T) -> Traced<T> {
+                    $imp::$method(self, Traced::new(other))
+                }
+            }
+
+            impl<'a, T: Copy> $imp<&'a Traced<T>> for Traced<T>
+            where
+                Checked<T>: $imp<T, Output = Checked<T>>,
+                Value: From<T>,
+            {
+                type Output = Traced<T>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: &'a Traced<T>) -> Traced<T> {
+                    $imp::$method(self, other.clone())
+                }
+            }
+
+            impl<'a, T: Clone, U> $imp<U> for &'a Traced<T>
+            where
+                Traced<T>: $imp<U, Output = Traced<T>>,
+            {
+                type Output = Traced<T>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: U) -> Traced<T> {
+                    $imp::$method(self.clone(), other)
+                }
+            }
+
+            impl<T, U> $imp_assign<U> for Traced<T>
+            where
+                Traced<T>: $imp<U, Output = Traced<T>>,
+            {
+                #[inline]
+                #[track_caller]
+                fn $method_assign(&mut self, other: U) {
+                    *self = $imp::$method(mem::replace(self, Traced::EMPTY), other);
+                }
+            }
+        )*
+    };
+}
+
+impl_traced_binop! {
+    Add, add, AddAssign, add_assign {+};
+    Sub, sub, SubAssign, sub_assign {-};
+    Mul, mul, MulAssign, mul_assign {*};
+    Div, div, DivAssign, div_assign {/};
+    Rem, rem, RemAssign, rem_assign {%};
+    BitAnd, bitand, BitAndAssign, bitand_assign {&};
+    BitOr, bitor, BitOrAssign, bitor_assign {|};
+    BitXor, bitxor, BitXorAssign, bitxor_assign {^};
+}
+
+// implements shifts by `u32`, as for `Checked`
+macro_rules! impl_traced_sh {
+    ($($imp:ident, $method:ident, $imp_assign:ident, $method_assign:ident {$sym:tt};)*) => {
+        $(
+            impl<T: Copy> $imp<u32> for Traced<T>
+            where
+                Checked<T>: $imp<u32, Output = Checked<T>>,
+                Value: From<T>,
+            {
+                type Output = Traced<T>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: u32) -> Traced<T> {
+                    let result = match self.value {
+                        Some(x) => $imp::$method(Checked::new(x), other).0,
+                        None => None,
+                    };
+                    record::<T, u32>(stringify!($sym), self, Some(other), Vec::new(), result)
+                }
+            }
+
+            impl<'a, T: Copy> $imp<u32> for &'a Traced<T>
+            where
+                Checked<T>: $imp<u32, Output = Checked<T>>,
+                Value: From<T>,
+            {
+                type Output = Traced<T>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: u32) -> Traced<T> {
+                    $imp::$method(self.clone(), other)
+                }
+            }
+
+            impl<T: Copy> $imp_assign<u32> for Traced<T>
+            where
+                Checked<T>: $imp<u32, Output = Checked<T>>,
+                Value: From<T>,
+            {
+                #[inline]
+                #[track_caller]
+                fn $method_assign(&mut self, other: u32) {
+                    *self = $imp::$method(mem::replace(self, Traced::EMPTY), other);
+                }
+            }
+        )*
+    };
+}
+
+impl_traced_sh! {
+    Shl, shl, ShlAssign, shl_assign {<<};
+    Shr, shr, ShrAssign, shr_assign {>>};
+}
+
+// implements unary operators by those of `Checked`
+macro_rules! impl_traced_unop {
+    ($($imp:ident, $method:ident {$sym:tt};)*) => {
+        $(
+            impl<T: Copy> $imp for Traced<T>
+            where
+                Checked<T>: $imp<Output = Checked<T>>,
+                Value: From<T>,
+            {
+                type Output = Traced<T>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self) -> Traced<T> {
+                    let mut steps = self.steps;
+                    let value = match self.value {
+                        Some(x) => {
+                            let result = $imp::$method(Checked::new(x)).0;
+                            steps.push(Step {
+                                symbol: stringify!($sym),
+                                lhs: Value::from(x),
+                                rhs: None,
+                                result: result.map(Value::from),
+                            });
+                            result
+                        }
+                        None => None,
+                    };
+                    Traced { value, steps }
+                }
+            }
+
+            impl<'a, T: Copy> $imp for &'a Traced<T>
+            where
+                Checked<T>: $imp<Output = Checked<T>>,
+                Value: From<T>,
+            {
+                type Output = Traced<T>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self) -> Traced<T> {
+                    $imp::$method(self.clone())
+                }
+            }
+        )*
+    };
+}
+
+impl_traced_unop! {
+    Neg, neg {-};
+    Not, not {!};
+}
+
+// implements `T op Traced<T>` for the primitives, which can't be done generically
+macro_rules! impl_traced_reverse {
+    ($($t:ident)*) => {
+        $(
+            impl_traced_reverse! { @ops $t: Add, add; Sub, sub; Mul, mul; Div, div; Rem, rem;
+                BitAnd, bitand; BitOr, bitor; BitXor, bitxor; }
+        )*
+    };
+    (@ops $t:ident: $($imp:ident, $method:ident;)*) => {
+        $(
+            impl $imp<Traced<$t>> for $t {
+                type Output = Traced<$t>;
+
+                #[inline]
+                #[track_caller]
+                fn $method(self, other: Traced<$t>) -> Traced<$t> {
+                    $imp::$method(Traced::new(self), other)
+                }
+            }
+        )*
+    };
+}
+
+impl_traced_reverse! { u8 u16 u32 u64 usize i8 i16 i32 i64 isize }
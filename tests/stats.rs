@@ -0,0 +1,35 @@
+//! The counters are process-wide, so this is the only test in its binary: no other test counts
+//! operations at the same time.
+#![cfg(feature = "stats")]
+
+extern crate checked;
+
+use checked::{stats, Checked, Op};
+
+#[test]
+fn statistics() {
+    let before = stats::snapshot();
+    let x = Checked::new(40_000_usize);
+    let _ = x * x;
+    let _ = x - 50_000;
+    let _ = x - 40_000;
+    let _ = -(x + 1);
+    let _ = x << 70;
+    let _ = (x - 50_000) * 2; // already failed, so not counted again
+    let counts = stats::snapshot().since(&before);
+
+    let sub = counts.get("usize", Op::Sub).unwrap();
+    assert_eq!((sub.operations, sub.failures), (3, 2));
+    assert_eq!(counts.get("usize", Op::Neg).unwrap(), stats::Counts { operations: 1, failures: 1 });
+    assert_eq!(counts.get("usize", Op::Mul).unwrap().operations, 1);
+    let usize_counts = counts.by_type("usize").unwrap();
+    assert_eq!((usize_counts.operations, usize_counts.failures), (7, 4));
+    assert_eq!(usize_counts.failure_rate(), 4.0 / 7.0);
+    assert_eq!(counts.by_op(Op::Shl), stats::Counts { operations: 1, failures: 1 });
+    assert_eq!(counts.total(), usize_counts);
+    assert_eq!(counts.get("u128", Op::Add), None);
+    assert_eq!(counts.iter().filter(|&(t, _, c)| t == "usize" && c.operations > 0).count(), 5);
+
+    stats::reset();
+    assert_eq!(stats::snapshot().total(), stats::Counts::default());
+}